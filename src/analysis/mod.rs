@@ -0,0 +1,4 @@
+// questions about filled grids that minimum clue research asks
+pub mod unavoidable;
+
+pub use unavoidable::unavoidable_sets;
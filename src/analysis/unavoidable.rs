@@ -0,0 +1,117 @@
+// unavoidable sets of a filled grid: cells whose digits can be rearranged into another grid that
+// follows all the rules, so a puzzle with that solution needs a given in every one of them
+// the digits of an unavoidable set of n cells are moved around among themselves, which takes at
+// least two cells for every digit, so at most n / 2 different digits are involved; for every
+// choice of that many digits the cells holding them are emptied and the other ways to fill them
+// in give the sets, of which the ones without a smaller set inside are kept
+use crate::datastructures::digit::Digit;
+use crate::datastructures::sudoku::Sudoku;
+use std::collections::HashSet;
+
+// every way to pick count of digits, in order
+fn digit_choices(digits: &[Digit], count: usize) -> Vec<Vec<Digit>> {
+    if count == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (position, digit) in digits.iter().enumerate() {
+        for mut rest in digit_choices(&digits[position + 1..], count - 1) {
+            rest.insert(0, *digit);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+// the unavoidable sets of at most max_size cells that contain no smaller unavoidable set, smallest
+// first; None when the grid is not filled
+// the work grows quickly with max_size, every other grid that only differs in the cells of
+// max_size / 2 digits is looked at
+pub fn unavoidable_sets(sudoku: &Sudoku, max_size: usize) -> Option<Vec<Vec<usize>>> {
+    if !sudoku.is_solved() {
+        return None;
+    }
+    // two digits swapped in two lines is the smallest set there can be
+    if max_size < 4 {
+        return Some(Vec::new());
+    }
+    let digits = sudoku.get_digits();
+    let count = (max_size / 2).min(digits.len());
+    let mut found: HashSet<Vec<usize>> = HashSet::new();
+    for choice in digit_choices(&digits, count) {
+        let mut puzzle = sudoku.clone();
+        let emptied: Vec<usize> = sudoku
+            .cells()
+            .filter(|cell| cell.get_value().is_some_and(|d| choice.contains(&d)))
+            .map(|cell| *cell.get_index())
+            .collect();
+        for index in &emptied {
+            puzzle.get_mut_cell(*index).unwrap().set_value(None);
+        }
+        puzzle.for_each_solution(usize::MAX, |other| {
+            let cells: Vec<usize> = emptied
+                .iter()
+                .copied()
+                .filter(|index| {
+                    other.get_cell(*index).unwrap().get_value()
+                        != sudoku.get_cell(*index).unwrap().get_value()
+                })
+                .collect();
+            if !cells.is_empty() && cells.len() <= max_size {
+                found.insert(cells);
+            }
+        });
+    }
+    let mut sets: Vec<Vec<usize>> = found.into_iter().collect();
+    sets.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    let mut minimal: Vec<Vec<usize>> = Vec::new();
+    for set in sets {
+        if !minimal
+            .iter()
+            .any(|smaller| smaller.iter().all(|cell| set.contains(cell)))
+        {
+            minimal.push(set);
+        }
+    }
+    Some(minimal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_unavoidable_sets() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let solution = puzzle.get_solution().unwrap();
+        let sets = unavoidable_sets(&solution, 6).unwrap();
+        assert!(!sets.is_empty());
+        assert!(sets.iter().all(|set| set.len() >= 4 && set.len() <= 6));
+        for set in &sets {
+            // emptying the set lets the grid be filled in another way
+            let mut open = solution.clone();
+            for index in set {
+                open.get_mut_cell(*index).unwrap().set_value(None);
+            }
+            assert!(open.count_solutions(2) > 1);
+            // the puzzle has a unique solution so it has a given in every set
+            assert!(set
+                .iter()
+                .any(|index| !puzzle.get_cell(*index).unwrap().is_empty()));
+        }
+        assert!(unavoidable_sets(&puzzle, 6).is_none());
+        assert_eq!(unavoidable_sets(&solution, 3), Some(Vec::new()));
+    }
+    #[test]
+    fn test_unavoidable_rectangles() {
+        // two pairs of cells in a line holding the same two digits the other way round
+        let sudoku = Sudoku::from_line("1234341221434321").unwrap();
+        let sets = unavoidable_sets(&sudoku, 4).unwrap();
+        assert!(sets.contains(&vec![0, 1, 8, 9]));
+        assert_eq!(sets.len(), 8);
+        assert!(sets.iter().all(|set| set.len() == 4));
+    }
+}
@@ -0,0 +1,267 @@
+// killer cages: the digits of a cage add up to its sum and are all different
+use super::constraint::Constraint;
+use super::digit::{Digit, DigitHashSet};
+use super::sudoku::Sudoku;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cage {
+    cells: Vec<usize>,
+    sum: usize,
+    // false for cells from different houses that only share a total, as the 45 rule finds them
+    distinct: bool,
+}
+
+// whether one value from every option list, all different when distinct, adds up to target
+fn can_complete(
+    options: &[Vec<usize>],
+    target: usize,
+    used: &mut Vec<usize>,
+    distinct: bool,
+) -> bool {
+    let (first, rest) = match options.split_first() {
+        Some(split) => split,
+        None => return target == 0,
+    };
+    let lowest: usize = options
+        .iter()
+        .map(|o| o.iter().min().copied().unwrap_or(0))
+        .sum();
+    let highest: usize = options
+        .iter()
+        .map(|o| o.iter().max().copied().unwrap_or(0))
+        .sum();
+    if lowest > target || highest < target || options.iter().any(|o| o.is_empty()) {
+        return false;
+    }
+    for value in first {
+        if *value > target || (distinct && used.contains(value)) {
+            continue;
+        }
+        used.push(*value);
+        let found = can_complete(rest, target - value, used, distinct);
+        used.pop();
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+impl Cage {
+    pub fn new(cells: Vec<usize>, sum: usize) -> Self {
+        Cage {
+            cells,
+            sum,
+            distinct: true,
+        }
+    }
+    // cells that add up to sum but may repeat digits
+    pub fn new_with_repeats(cells: Vec<usize>, sum: usize) -> Self {
+        Cage {
+            cells,
+            sum,
+            distinct: false,
+        }
+    }
+    pub fn get_cells(&self) -> &Vec<usize> {
+        &self.cells
+    }
+    pub fn get_sum(&self) -> &usize {
+        &self.sum
+    }
+    pub fn is_distinct(&self) -> bool {
+        self.distinct
+    }
+    // every set of digits that fills the cage, each set in increasing order
+    pub fn combinations(&self, digits: &[Digit]) -> Vec<Vec<Digit>> {
+        fn collect(
+            digits: &[Digit],
+            size: usize,
+            target: usize,
+            chosen: &mut Vec<Digit>,
+            result: &mut Vec<Vec<Digit>>,
+        ) {
+            if size == 0 {
+                if target == 0 {
+                    result.push(chosen.clone());
+                }
+                return;
+            }
+            for (position, digit) in digits.iter().enumerate() {
+                if digit.to_usize() > target {
+                    continue;
+                }
+                chosen.push(*digit);
+                collect(
+                    &digits[position + 1..],
+                    size - 1,
+                    target - digit.to_usize(),
+                    chosen,
+                    result,
+                );
+                chosen.pop();
+            }
+        }
+        let mut result = Vec::new();
+        collect(
+            digits,
+            self.cells.len(),
+            self.sum,
+            &mut Vec::new(),
+            &mut result,
+        );
+        result
+    }
+    // whether the cells can reach the sum at all with the digits of the grid
+    pub fn is_possible(&self, digits: &[Digit]) -> bool {
+        let values: Vec<usize> = digits.iter().map(|d| d.to_usize()).collect();
+        let options = vec![values; self.cells.len()];
+        !self.cells.is_empty() && can_complete(&options, self.sum, &mut Vec::new(), self.distinct)
+    }
+    fn placed(&self, sudoku: &Sudoku) -> Vec<usize> {
+        self.cells
+            .iter()
+            .filter_map(|i| sudoku.get_cell(*i).and_then(|c| *c.get_value()))
+            .map(|d| d.to_usize())
+            .collect()
+    }
+}
+
+impl Constraint for Cage {
+    fn get_name(&self) -> String {
+        format!("cage {}", self.sum)
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+    // the sum can only be checked exactly once the cage is full, before that the placed digits
+    // must leave room for the rest
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        let placed = self.placed(sudoku);
+        let total: usize = placed.iter().sum();
+        if total > self.sum {
+            return false;
+        }
+        if self.distinct && (1..placed.len()).any(|i| placed[..i].contains(&placed[i])) {
+            return false;
+        }
+        let mut free: Vec<usize> = sudoku
+            .get_digits()
+            .iter()
+            .map(|d| d.to_usize())
+            .filter(|value| !self.distinct || !placed.contains(value))
+            .collect();
+        free.sort_unstable();
+        let empty = self.cells.len() - placed.len();
+        let rest = self.sum - total;
+        if empty == 0 {
+            return rest == 0;
+        }
+        let (lowest, highest): (usize, usize) = if self.distinct {
+            if free.len() < empty {
+                return false;
+            }
+            (
+                free[..empty].iter().sum(),
+                free[free.len() - empty..].iter().sum(),
+            )
+        } else {
+            (empty * free[0], empty * free[free.len() - 1])
+        };
+        lowest <= rest && rest <= highest
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let placed = self.placed(sudoku);
+        let total: usize = placed.iter().sum();
+        let empty: Vec<usize> = self
+            .cells
+            .iter()
+            .copied()
+            .filter(|i| sudoku.get_cell(*i).is_some_and(|c| c.is_empty()))
+            .collect();
+        if total > self.sum {
+            for index in empty {
+                candidates[index].clear();
+            }
+            return;
+        }
+        let options: Vec<Vec<usize>> = empty
+            .iter()
+            .map(|i| candidates[*i].iter().map(|d| d.to_usize()).collect())
+            .collect();
+        for (position, index) in empty.iter().enumerate() {
+            let mut others = options.clone();
+            others.remove(position);
+            candidates[*index].retain(|digit| {
+                let value = digit.to_usize();
+                let mut used = placed.clone();
+                if value > self.sum - total || (self.distinct && used.contains(&value)) {
+                    return false;
+                }
+                used.push(value);
+                can_complete(&others, self.sum - total - value, &mut used, self.distinct)
+            });
+        }
+    }
+    fn sees(&self, first: usize, second: usize) -> bool {
+        self.distinct
+            && first != second
+            && self.cells.contains(&first)
+            && self.cells.contains(&second)
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Digit::*;
+
+    #[test]
+    fn test_cage_combinations() {
+        let cage = Cage::new(vec![0, 1, 2], 7);
+        assert_eq!(cage.combinations(&Digit::all()), vec![vec![One, Two, Four]]);
+        assert!(cage.is_possible(&Digit::all()));
+        assert!(!Cage::new(vec![0, 1], 18).is_possible(&Digit::all()));
+        assert!(Cage::new_with_repeats(vec![0, 1], 18).is_possible(&Digit::all()));
+    }
+    #[test]
+    fn test_cage_restricts_candidates() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_cage(Cage::new(vec![0, 1], 4));
+        let expected: DigitHashSet = [One, Three].iter().copied().collect();
+        assert_eq!(sudoku.get_cell(0).unwrap().get_candidates(), &expected);
+        sudoku.set_digit(0, One);
+        assert_eq!(sudoku.get_cell(1).unwrap().get_candidates().len(), 1);
+        assert!(sudoku.get_cell(1).unwrap().has_candidate(Three));
+    }
+    #[test]
+    fn test_cage_among_constraints() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_cage(Cage::new(vec![0, 1], 4));
+        sudoku.add_diagonals();
+        let rule = &sudoku.get_constraints()[0];
+        assert!(rule.is::<Cage>());
+        assert_eq!(rule.get_name(), "cage 4");
+        assert_eq!(
+            sudoku
+                .get_cages()
+                .map(|cage| *cage.get_sum())
+                .sum::<usize>(),
+            4
+        );
+        assert!(!sudoku.add_cage(Cage::new(vec![1, 2], 3)));
+    }
+    #[test]
+    fn test_cage_validity() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let cage = Cage::new(vec![0, 1, 2], 24);
+        sudoku.get_mut_cell(0).unwrap().set_value(Some(Seven));
+        assert!(cage.is_valid(&sudoku));
+        sudoku.get_mut_cell(1).unwrap().set_value(Some(Six));
+        // eleven is left for the last cell
+        assert!(!cage.is_valid(&sudoku));
+    }
+}
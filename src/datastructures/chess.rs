@@ -0,0 +1,159 @@
+// chess variants: two cells a knight's or a king's move apart never hold the same digit
+use super::constraint::Constraint;
+use super::digit::DigitHashSet;
+use super::griddimensions::GridDimensions;
+use super::sudoku::Sudoku;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChessPiece {
+    Knight,
+    King,
+}
+
+impl ChessPiece {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ChessPiece::Knight => "anti-knight",
+            ChessPiece::King => "anti-king",
+        }
+    }
+    // the column and row steps of one move
+    pub fn get_moves(&self) -> &'static [(isize, isize)] {
+        match self {
+            ChessPiece::Knight => &[
+                (1, 2),
+                (2, 1),
+                (2, -1),
+                (1, -2),
+                (-1, -2),
+                (-2, -1),
+                (-2, 1),
+                (-1, 2),
+            ],
+            ChessPiece::King => &[
+                (1, 0),
+                (1, 1),
+                (0, 1),
+                (-1, 1),
+                (-1, 0),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChessConstraint {
+    piece: ChessPiece,
+    columns: usize,
+    rows: usize,
+}
+
+impl ChessConstraint {
+    pub fn new(piece: ChessPiece, grid_dimensions: &GridDimensions) -> Self {
+        ChessConstraint {
+            piece,
+            columns: *grid_dimensions.get_column_count(),
+            rows: *grid_dimensions.get_row_count(),
+        }
+    }
+    pub fn get_piece(&self) -> &ChessPiece {
+        &self.piece
+    }
+    // the cells one move away from index
+    pub fn reached_from(&self, index: usize) -> Vec<usize> {
+        let (column, row) = (
+            (index % self.columns) as isize,
+            (index / self.columns) as isize,
+        );
+        self.piece
+            .get_moves()
+            .iter()
+            .map(|(x, y)| (column + x, row + y))
+            .filter(|(x, y)| {
+                *x >= 0 && *y >= 0 && (*x as usize) < self.columns && (*y as usize) < self.rows
+            })
+            .map(|(x, y)| y as usize * self.columns + x as usize)
+            .collect()
+    }
+}
+
+impl Constraint for ChessConstraint {
+    fn get_name(&self) -> String {
+        String::from(self.piece.get_name())
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        (0..self.columns * self.rows).collect()
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        sudoku.cells().all(|cell| match cell.get_value() {
+            Some(digit) => self
+                .reached_from(*cell.get_index())
+                .iter()
+                .all(|other| sudoku.get_cell(*other).unwrap().get_value() != &Some(*digit)),
+            None => true,
+        })
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        for cell in sudoku.cells() {
+            if let Some(digit) = cell.get_value() {
+                for other in self.reached_from(*cell.get_index()) {
+                    candidates[other].remove(digit);
+                }
+            }
+        }
+    }
+    fn sees(&self, first: usize, second: usize) -> bool {
+        self.reached_from(first).contains(&second)
+    }
+    fn is_pairwise(&self) -> bool {
+        true
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::Digit;
+
+    #[test]
+    fn test_moves_stay_on_the_grid() {
+        let dimensions = GridDimensions::new(9, 9, 3, 3);
+        let knight = ChessConstraint::new(ChessPiece::Knight, &dimensions);
+        assert_eq!(knight.reached_from(0), vec![19, 11]);
+        assert_eq!(knight.reached_from(40).len(), 8);
+        let king = ChessConstraint::new(ChessPiece::King, &dimensions);
+        assert_eq!(king.reached_from(80).len(), 3);
+        assert!(king.sees(0, 10));
+        assert!(!king.sees(0, 20));
+    }
+    #[test]
+    fn test_anti_knight_and_anti_king() {
+        for piece in [ChessPiece::Knight, ChessPiece::King] {
+            let mut sudoku = Sudoku::new(9, 9, 3, 3);
+            sudoku.add_chess_constraint(piece);
+            assert!(!sudoku.is_standard());
+            sudoku.set_digit(0, Digit::One);
+            let reached = ChessConstraint::new(piece, sudoku.get_grid_dimensions()).reached_from(0);
+            for index in &reached {
+                assert!(!sudoku.get_cell(*index).unwrap().has_candidate(Digit::One));
+            }
+            let solution = sudoku.get_solution().unwrap();
+            assert!(solution.is_solved());
+            assert!(solution.is_consistent());
+            let mut broken = Sudoku::new(9, 9, 3, 3);
+            broken.add_chess_constraint(piece);
+            broken.get_mut_cell(0).unwrap().set_value(Some(Digit::One));
+            broken
+                .get_mut_cell(reached[0])
+                .unwrap()
+                .set_value(Some(Digit::One));
+            assert!(!broken.is_consistent());
+        }
+    }
+}
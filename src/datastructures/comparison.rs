@@ -0,0 +1,185 @@
+// rules on the size of digits: shaded cells that hold only odd or only even digits, and
+// inequality signs between two cells next to each other
+use super::constraint::{options, value_at, Constraint, Marking};
+use super::digit::DigitHashSet;
+use super::sudoku::Sudoku;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parity {
+    cells: Vec<usize>,
+    even: bool,
+}
+
+impl Parity {
+    pub fn new(cells: Vec<usize>, even: bool) -> Self {
+        Parity { cells, even }
+    }
+    pub fn is_even(&self) -> bool {
+        self.even
+    }
+    fn fits(&self, value: usize) -> bool {
+        value.is_multiple_of(2) == self.even
+    }
+}
+
+impl Constraint for Parity {
+    fn get_name(&self) -> String {
+        String::from(if self.even { "even" } else { "odd" })
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        self.cells
+            .iter()
+            .all(|index| value_at(sudoku, *index).is_none_or(|value| self.fits(value)))
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        for index in &self.cells {
+            if sudoku.get_cell(*index).is_some_and(|cell| cell.is_empty()) {
+                candidates[*index].retain(|digit| self.fits(digit.to_usize()));
+            }
+        }
+    }
+    // even cells are shaded squares and odd ones circles
+    fn get_markings(&self) -> Vec<Marking> {
+        self.cells
+            .iter()
+            .map(|cell| Marking::Shade {
+                cell: *cell,
+                round: !self.even,
+            })
+            .collect()
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+// the digit in smaller is less than the one in larger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    smaller: usize,
+    larger: usize,
+}
+
+impl Comparison {
+    pub fn new(smaller: usize, larger: usize) -> Self {
+        Comparison { smaller, larger }
+    }
+    pub fn get_smaller(&self) -> &usize {
+        &self.smaller
+    }
+    pub fn get_larger(&self) -> &usize {
+        &self.larger
+    }
+}
+
+impl Constraint for Comparison {
+    fn get_name(&self) -> String {
+        String::from("less than")
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        vec![self.smaller, self.larger]
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        match (
+            value_at(sudoku, self.smaller),
+            value_at(sudoku, self.larger),
+        ) {
+            (Some(smaller), Some(larger)) => smaller < larger,
+            _ => true,
+        }
+    }
+    // the smaller cell stays below the largest digit the larger cell can hold and the other way
+    // around, chains of signs follow as the sudoku propagates the changes
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let highest = options(sudoku, candidates, self.larger)
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        let lowest = options(sudoku, candidates, self.smaller)
+            .into_iter()
+            .min()
+            .unwrap_or(usize::MAX);
+        if sudoku
+            .get_cell(self.smaller)
+            .is_some_and(|cell| cell.is_empty())
+        {
+            candidates[self.smaller].retain(|digit| digit.to_usize() < highest);
+        }
+        if sudoku
+            .get_cell(self.larger)
+            .is_some_and(|cell| cell.is_empty())
+        {
+            candidates[self.larger].retain(|digit| digit.to_usize() > lowest);
+        }
+    }
+    // the sign points at the smaller cell
+    fn get_markings(&self) -> Vec<Marking> {
+        let columns_apart = self.smaller.abs_diff(self.larger) == 1;
+        let text = match (columns_apart, self.smaller < self.larger) {
+            (true, true) => "<",
+            (true, false) => ">",
+            (false, true) => "^",
+            (false, false) => "v",
+        };
+        vec![Marking::Label {
+            cells: (self.smaller.min(self.larger), self.smaller.max(self.larger)),
+            text: String::from(text),
+        }]
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::constraint::candidate_values as values;
+    use crate::datastructures::digit::Digit::*;
+
+    #[test]
+    fn test_parity() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(Parity::new(vec![0, 1, 2, 3], true)));
+        sudoku.add_constraint(Box::new(Parity::new(vec![80], false)));
+        assert_eq!(values(&sudoku, 0), vec![2, 4, 6, 8]);
+        assert_eq!(values(&sudoku, 80), vec![1, 3, 5, 7, 9]);
+        // four even cells in a row take every even digit
+        let solution = sudoku.get_solution().unwrap();
+        assert!((4..9).all(|i| solution
+            .get_cell(i)
+            .unwrap()
+            .get_value()
+            .unwrap()
+            .to_usize()
+            % 2
+            == 1));
+    }
+    #[test]
+    fn test_comparison_chain() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        for (smaller, larger) in [(0, 1), (1, 2), (2, 3)] {
+            sudoku.add_constraint(Box::new(Comparison::new(smaller, larger)));
+        }
+        assert_eq!(values(&sudoku, 0), (1..=6).collect::<Vec<usize>>());
+        assert_eq!(values(&sudoku, 3), (4..=9).collect::<Vec<usize>>());
+        // the change at the bottom of the chain reaches its top
+        sudoku.set_digit(0, Five);
+        assert_eq!(values(&sudoku, 3), vec![8, 9]);
+        let mut broken = Sudoku::new(9, 9, 3, 3);
+        broken.add_constraint(Box::new(Comparison::new(9, 0)));
+        broken.get_mut_cell(0).unwrap().set_value(Some(One));
+        broken.get_mut_cell(9).unwrap().set_value(Some(Two));
+        assert!(!broken.is_consistent());
+        assert_eq!(
+            Comparison::new(9, 0).get_markings(),
+            vec![Marking::Label {
+                cells: (0, 9),
+                text: String::from("v")
+            }]
+        );
+    }
+}
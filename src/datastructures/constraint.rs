@@ -0,0 +1,134 @@
+// a rule the digits of a grid have to follow; rows, columns and sections are the usual ones,
+// variants add their own next to them
+use super::digit::DigitHashSet;
+use super::sudoku::Sudoku;
+use std::any::Any;
+use std::fmt;
+
+// how a rule is shown on a picture of the grid, cells are given by their index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Marking {
+    // a thick line through the centres of the cells, in order
+    Line {
+        cells: Vec<usize>,
+        color: &'static str,
+    },
+    // a thin line through the centres of the cells that ends in an arrow head
+    Arrow {
+        cells: Vec<usize>,
+    },
+    // a circle on a cell, filled like the bulb of a thermometer or open like the head of an arrow
+    Circle {
+        cell: usize,
+        filled: bool,
+    },
+    // a gray square or circle in the background of a cell
+    Shade {
+        cell: usize,
+        round: bool,
+    },
+    // a dot on the edge between two cells, black when filled
+    Dot {
+        cells: (usize, usize),
+        filled: bool,
+    },
+    // a short text on the edge between two cells
+    Label {
+        cells: (usize, usize),
+        text: String,
+    },
+    // a clue in the margin at a row and column just outside the grid, with a small arrow for clues
+    // that look along a diagonal
+    Outside {
+        position: (isize, isize),
+        text: String,
+        direction: Option<(isize, isize)>,
+    },
+}
+
+// the digit in a cell as a number, None for an empty cell
+pub(crate) fn value_at(sudoku: &Sudoku, index: usize) -> Option<usize> {
+    sudoku
+        .get_cell(index)
+        .and_then(|cell| *cell.get_value())
+        .map(|digit| digit.to_usize())
+}
+
+// the digits a cell can hold as numbers in increasing order, its own when it is filled
+pub(crate) fn options(sudoku: &Sudoku, candidates: &[DigitHashSet], index: usize) -> Vec<usize> {
+    match value_at(sudoku, index) {
+        Some(value) => vec![value],
+        None => {
+            let mut values: Vec<usize> = candidates[index].iter().map(|d| d.to_usize()).collect();
+            values.sort_unstable();
+            values
+        }
+    }
+}
+
+// the candidates of a cell as numbers in increasing order, for the tests of the rules
+#[cfg(test)]
+pub(crate) fn candidate_values(sudoku: &Sudoku, index: usize) -> Vec<usize> {
+    let mut values: Vec<usize> = sudoku
+        .get_cell(index)
+        .unwrap()
+        .get_candidates()
+        .iter()
+        .map(|d| d.to_usize())
+        .collect();
+    values.sort_unstable();
+    values
+}
+
+pub trait Constraint: Any + fmt::Debug {
+    fn get_name(&self) -> String;
+    // the cells the rule is about
+    fn get_cells(&self) -> Vec<usize>;
+    // false when the digits placed so far break the rule, empty cells never do
+    fn is_valid(&self, sudoku: &Sudoku) -> bool;
+    // removes the candidates the digits placed so far rule out, there is one set for every cell
+    // of the grid; by default every candidate of the rule's empty cells is tried in turn
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let mut work = sudoku.clone();
+        for index in self.get_cells() {
+            if !sudoku.get_cell(index).is_some_and(|c| c.is_empty()) {
+                continue;
+            }
+            candidates[index].retain(|digit| {
+                work.get_mut_cell(index).unwrap().set_value(Some(*digit));
+                let valid = self.is_valid(&work);
+                work.get_mut_cell(index).unwrap().set_value(None);
+                valid
+            });
+        }
+    }
+    // true when the rule keeps the two cells from holding the same digit
+    fn sees(&self, _first: usize, _second: usize) -> bool {
+        false
+    }
+    // true when sees says all there is to the rule, the peers then enforce it on their own
+    fn is_pairwise(&self) -> bool {
+        false
+    }
+    // what the renderers draw for the rule, nothing by default
+    fn get_markings(&self) -> Vec<Marking> {
+        Vec::new()
+    }
+    fn box_clone(&self) -> Box<dyn Constraint>;
+}
+
+impl dyn Constraint {
+    // the rule as the type it was made from, None for a rule of another type
+    pub fn downcast_ref<T: Constraint>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref::<T>()
+    }
+    pub fn is<T: Constraint>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+}
+
+impl Clone for Box<dyn Constraint> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
@@ -16,6 +16,50 @@ pub enum Digit {
 pub type DigitValue = Option<Digit>;
 pub type DigitHashSet = HashSet<Digit>;
 
+impl Digit {
+    pub fn all() -> Vec<Digit> {
+        use Digit::*;
+
+        vec![One, Two, Three, Four, Five, Six, Seven, Eight, Nine]
+    }
+    pub fn from_usize(value: usize) -> DigitValue {
+        if (1..=9).contains(&value) {
+            Some(Digit::all()[value - 1])
+        } else {
+            None
+        }
+    }
+    pub fn to_usize(self) -> usize {
+        use Digit::*;
+
+        match self {
+            One => 1,
+            Two => 2,
+            Three => 3,
+            Four => 4,
+            Five => 5,
+            Six => 6,
+            Seven => 7,
+            Eight => 8,
+            Nine => 9,
+        }
+    }
+    pub fn from_char(character: char) -> DigitValue {
+        character
+            .to_digit(10)
+            .and_then(|value| Digit::from_usize(value as usize))
+    }
+    pub fn to_char(self) -> char {
+        std::char::from_digit(self.to_usize() as u32, 10).unwrap()
+    }
+}
+
+impl Default for DigitSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DigitSet {
     data: Vec<DigitValue>,
@@ -57,9 +101,7 @@ impl DigitSet {
             Some(Nine),
         ];
         data.rotate_left(times);
-        DigitSet {
-            data: data,
-        }    
+        DigitSet { data }
     }
     pub fn contains(&self, digit: Digit) -> bool {
         self.data.contains(&Some(digit))
@@ -74,11 +116,7 @@ impl DigitSet {
         self.data.iter()
     }
     pub fn new_hashset(&self) -> DigitHashSet {
-        self.data
-            .iter()
-            .filter(|d| d.is_some())
-            .map(|d| d.unwrap())
-            .collect()
+        self.data.iter().flatten().copied().collect()
     }
     pub fn get_data(&self) -> Vec<DigitValue> {
         self.data.clone()
@@ -87,7 +125,7 @@ impl DigitSet {
 
 #[cfg(test)]
 mod tests {
-    use super::{Digit::*, DigitSet, DigitValue};
+    use super::{Digit, Digit::*, DigitSet, DigitValue};
 
     #[test]
     fn test_if_digit_can_be_none() {
@@ -97,20 +135,20 @@ mod tests {
     #[test]
     fn test_digit_set_contains_nothing() {
         let digitset = DigitSet::new();
-        assert_eq!(digitset.contains(One), false);
+        assert!(!digitset.contains(One));
     }
     #[test]
     fn test_digit_set_contains_all() {
         let digitset = DigitSet::new_full();
-        assert_eq!(digitset.contains(One), true);
-        assert_eq!(digitset.contains(Two), true);
-        assert_eq!(digitset.contains(Three), true);
-        assert_eq!(digitset.contains(Four), true);
-        assert_eq!(digitset.contains(Five), true);
-        assert_eq!(digitset.contains(Six), true);
-        assert_eq!(digitset.contains(Seven), true);
-        assert_eq!(digitset.contains(Eight), true);
-        assert_eq!(digitset.contains(Nine), true);
+        assert!(digitset.contains(One));
+        assert!(digitset.contains(Two));
+        assert!(digitset.contains(Three));
+        assert!(digitset.contains(Four));
+        assert!(digitset.contains(Five));
+        assert!(digitset.contains(Six));
+        assert!(digitset.contains(Seven));
+        assert!(digitset.contains(Eight));
+        assert!(digitset.contains(Nine));
     }
     #[test]
     fn test_digit_set_rotation() {
@@ -123,6 +161,17 @@ mod tests {
     #[test]
     fn test_digit_into_hashset() {
         let hashset = DigitSet::new_full().new_hashset();
-        assert_eq!(hashset.iter().count(), 9);
+        assert_eq!(hashset.len(), 9);
+    }
+    #[test]
+    fn test_digit_conversions() {
+        assert_eq!(Digit::from_usize(0), None);
+        assert_eq!(Digit::from_usize(10), None);
+        for digit in Digit::all() {
+            assert_eq!(Digit::from_usize(digit.to_usize()), Some(digit));
+            assert_eq!(Digit::from_char(digit.to_char()), Some(digit));
+        }
+        assert_eq!(Digit::from_char('.'), None);
+        assert_eq!(Digit::from_char('0'), None);
     }
 }
@@ -0,0 +1,290 @@
+// clues on the edge between two cells next to each other: kropki dots and xv marks; with the
+// negative rule every pair of neighbours that could carry a clue but has none breaks it
+use super::constraint::{options, value_at, Constraint, Marking};
+use super::digit::DigitHashSet;
+use super::sudoku::Sudoku;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    // the digits are consecutive
+    WhiteDot,
+    // one digit is double the other
+    BlackDot,
+    // the digits add up to ten
+    X,
+    // the digits add up to five
+    V,
+}
+
+impl EdgeKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            EdgeKind::WhiteDot => "white dot",
+            EdgeKind::BlackDot => "black dot",
+            EdgeKind::X => "x",
+            EdgeKind::V => "v",
+        }
+    }
+    pub fn allows(&self, first: usize, second: usize) -> bool {
+        match self {
+            EdgeKind::WhiteDot => first + 1 == second || second + 1 == first,
+            EdgeKind::BlackDot => first * 2 == second || second * 2 == first,
+            EdgeKind::X => first + second == 10,
+            EdgeKind::V => first + second == 5,
+        }
+    }
+}
+
+// keeps the candidates of both cells that have a partner in the other cell for which fits holds
+fn restrict_pair<F>(
+    sudoku: &Sudoku,
+    candidates: &mut [DigitHashSet],
+    first: usize,
+    second: usize,
+    fits: F,
+) where
+    F: Fn(usize, usize) -> bool,
+{
+    let first_options = options(sudoku, candidates, first);
+    let second_options = options(sudoku, candidates, second);
+    if sudoku.get_cell(first).is_some_and(|cell| cell.is_empty()) {
+        candidates[first].retain(|d| second_options.iter().any(|o| fits(d.to_usize(), *o)));
+    }
+    if sudoku.get_cell(second).is_some_and(|cell| cell.is_empty()) {
+        candidates[second].retain(|d| first_options.iter().any(|o| fits(*o, d.to_usize())));
+    }
+}
+
+// the pairs of cells in use that share an edge, each pair once with the smaller index first
+pub fn neighbour_pairs(sudoku: &Sudoku) -> Vec<(usize, usize)> {
+    let columns = *sudoku.get_grid_dimensions().get_column_count();
+    let cell_count = sudoku.get_grid_dimensions().get_data_size();
+    let mut pairs = Vec::new();
+    for index in (0..cell_count).filter(|i| sudoku.is_active(*i)) {
+        if (index + 1) % columns != 0 && sudoku.is_active(index + 1) {
+            pairs.push((index, index + 1));
+        }
+        if index + columns < cell_count && sudoku.is_active(index + columns) {
+            pairs.push((index, index + columns));
+        }
+    }
+    pairs
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeClue {
+    kind: EdgeKind,
+    first: usize,
+    second: usize,
+}
+
+impl EdgeClue {
+    pub fn new(kind: EdgeKind, first: usize, second: usize) -> Self {
+        EdgeClue {
+            kind,
+            first: first.min(second),
+            second: first.max(second),
+        }
+    }
+    pub fn get_kind(&self) -> &EdgeKind {
+        &self.kind
+    }
+    pub fn get_pair(&self) -> (usize, usize) {
+        (self.first, self.second)
+    }
+}
+
+impl Constraint for EdgeClue {
+    fn get_name(&self) -> String {
+        String::from(self.kind.get_name())
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        vec![self.first, self.second]
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        match (value_at(sudoku, self.first), value_at(sudoku, self.second)) {
+            (Some(first), Some(second)) => self.kind.allows(first, second),
+            _ => true,
+        }
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        restrict_pair(sudoku, candidates, self.first, self.second, |a, b| {
+            self.kind.allows(a, b)
+        });
+    }
+    fn get_markings(&self) -> Vec<Marking> {
+        let cells = (self.first, self.second);
+        match self.kind {
+            EdgeKind::WhiteDot => vec![Marking::Dot {
+                cells,
+                filled: false,
+            }],
+            EdgeKind::BlackDot => vec![Marking::Dot {
+                cells,
+                filled: true,
+            }],
+            EdgeKind::X | EdgeKind::V => vec![Marking::Label {
+                cells,
+                text: self.kind.get_name().to_uppercase(),
+            }],
+        }
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+// no clue of kinds on any of the pairs, used for the neighbours without a clue when all clues of
+// those kinds are given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeEdges {
+    kinds: Vec<EdgeKind>,
+    pairs: Vec<(usize, usize)>,
+}
+
+impl NegativeEdges {
+    pub fn new(kinds: Vec<EdgeKind>, pairs: Vec<(usize, usize)>) -> Self {
+        NegativeEdges { kinds, pairs }
+    }
+    // every pair of neighbours in sudoku that carries none of clues
+    pub fn without_clues(kinds: Vec<EdgeKind>, sudoku: &Sudoku, clues: &[EdgeClue]) -> Self {
+        let pairs = neighbour_pairs(sudoku)
+            .into_iter()
+            .filter(|pair| !clues.iter().any(|clue| clue.get_pair() == *pair))
+            .collect();
+        NegativeEdges::new(kinds, pairs)
+    }
+    pub fn get_kinds(&self) -> &Vec<EdgeKind> {
+        &self.kinds
+    }
+    fn fits(&self, first: usize, second: usize) -> bool {
+        !self.kinds.iter().any(|kind| kind.allows(first, second))
+    }
+}
+
+impl Constraint for NegativeEdges {
+    fn get_name(&self) -> String {
+        let names: Vec<&str> = self.kinds.iter().map(|kind| kind.get_name()).collect();
+        format!("no {}", names.join(" or "))
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        let mut cells: Vec<usize> = self.pairs.iter().flat_map(|(a, b)| [*a, *b]).collect();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        self.pairs.iter().all(|(first, second)| {
+            match (value_at(sudoku, *first), value_at(sudoku, *second)) {
+                (Some(a), Some(b)) => self.fits(a, b),
+                _ => true,
+            }
+        })
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        for (first, second) in &self.pairs {
+            restrict_pair(sudoku, candidates, *first, *second, |a, b| self.fits(a, b));
+        }
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+// no two neighbours hold consecutive digits, the negative white dot rule on every pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonConsecutive {
+    rule: NegativeEdges,
+}
+
+impl NonConsecutive {
+    pub fn new(sudoku: &Sudoku) -> Self {
+        NonConsecutive {
+            rule: NegativeEdges::new(vec![EdgeKind::WhiteDot], neighbour_pairs(sudoku)),
+        }
+    }
+}
+
+impl Constraint for NonConsecutive {
+    fn get_name(&self) -> String {
+        String::from("non-consecutive")
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        self.rule.get_cells()
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        self.rule.is_valid(sudoku)
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        self.rule.restrict_candidates(sudoku, candidates);
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::Digit::*;
+
+    #[test]
+    fn test_edge_clues() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(EdgeClue::new(EdgeKind::BlackDot, 1, 0)));
+        sudoku.add_constraint(Box::new(EdgeClue::new(EdgeKind::V, 9, 18)));
+        // five, seven and nine have no half or double
+        assert_eq!(sudoku.get_cell(0).unwrap().get_candidates().len(), 6);
+        assert!(!sudoku.get_cell(9).unwrap().has_candidate(Five));
+        sudoku.set_digit(0, Three);
+        assert!(sudoku.get_cell(1).unwrap().has_candidate(Six));
+        assert_eq!(sudoku.get_cell(1).unwrap().get_candidates().len(), 1);
+        assert!(EdgeKind::WhiteDot.allows(4, 3));
+        assert!(EdgeKind::X.allows(1, 9));
+    }
+    // the dots of kinds a solution has, first on the pair if more than one fits
+    fn dots(sudoku: &Sudoku, line: &str, kinds: &[EdgeKind]) -> Vec<EdgeClue> {
+        let solution: Vec<usize> = line
+            .chars()
+            .map(|c| c.to_digit(10).unwrap() as usize)
+            .collect();
+        let mut clues = Vec::new();
+        for (first, second) in neighbour_pairs(sudoku) {
+            if let Some(kind) = kinds
+                .iter()
+                .find(|kind| kind.allows(solution[first], solution[second]))
+            {
+                clues.push(EdgeClue::new(*kind, first, second));
+            }
+        }
+        clues
+    }
+
+    #[test]
+    fn test_kropki_with_negative_rule() {
+        let mut sudoku = Sudoku::from_line("................").unwrap();
+        assert_eq!(neighbour_pairs(&sudoku).len(), 24);
+        let kinds = vec![EdgeKind::WhiteDot, EdgeKind::BlackDot];
+        let clues = dots(&sudoku, "1234341221434321", &kinds);
+        let negative = NegativeEdges::without_clues(kinds, &sudoku, &clues);
+        for clue in clues {
+            sudoku.add_constraint(Box::new(clue));
+        }
+        sudoku.add_constraint(Box::new(negative));
+        assert!(sudoku.has_unique_solution());
+        assert_eq!(sudoku.get_solution().unwrap().to_line(), "1234341221434321");
+    }
+    #[test]
+    fn test_negative_rule_removes_solutions() {
+        let mut sudoku = Sudoku::from_line("................").unwrap();
+        let clues = dots(&sudoku, "2143341212344321", &[EdgeKind::WhiteDot]);
+        let negative = NegativeEdges::without_clues(vec![EdgeKind::WhiteDot], &sudoku, &clues);
+        assert_eq!(negative.get_name(), "no white dot");
+        for clue in clues {
+            sudoku.add_constraint(Box::new(clue));
+        }
+        assert_eq!(sudoku.count_solutions(10), 4);
+        sudoku.add_constraint(Box::new(negative));
+        assert_eq!(sudoku.count_solutions(10), 2);
+    }
+}
@@ -1,4 +1,4 @@
-pub use super::digit::{Digit, DigitValue};
+pub use super::digit::{Digit, DigitHashSet, DigitValue};
 
 #[derive(Debug, Clone)]
 pub struct Cell {
@@ -7,16 +7,18 @@ pub struct Cell {
     row: usize,
     section: usize,
     value: DigitValue,
+    candidates: DigitHashSet,
 }
 
 impl Cell {
     fn new(index: usize, column: usize, row: usize, section: usize, value: DigitValue) -> Self {
         Cell {
-            index: index,
-            column: column,
-            row: row,
-            section: section,
-            value: value,
+            index,
+            column,
+            row,
+            section,
+            value,
+            candidates: DigitHashSet::new(),
         }
     }
     pub fn get_index(&self) -> &usize {
@@ -31,15 +33,38 @@ impl Cell {
     pub fn get_section(&self) -> &usize {
         &self.section
     }
+    // jigsaw grids move cells into irregular sections
+    pub fn set_section(&mut self, section: usize) {
+        self.section = section;
+    }
     pub fn get_value(&self) -> &DigitValue {
         &self.value
     }
     pub fn set_value(&mut self, value: DigitValue) {
         self.value = value;
     }
+    pub fn get_candidates(&self) -> &DigitHashSet {
+        &self.candidates
+    }
+    pub fn set_candidates(&mut self, candidates: DigitHashSet) {
+        self.candidates = candidates;
+    }
+    pub fn remove_candidate(&mut self, digit: Digit) -> bool {
+        self.candidates.remove(&digit)
+    }
+    pub fn has_candidate(&self, digit: Digit) -> bool {
+        self.candidates.contains(&digit)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.value.is_none()
+    }
+    // name of the cell in the usual r1c1 notation, rows and columns counted from one
+    pub fn get_name(&self) -> String {
+        format!("r{}c{}", self.row + 1, self.column + 1)
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GridDimensions {
     row_count: usize,
     column_count: usize,
@@ -52,14 +77,14 @@ impl GridDimensions {
         GridDimensions {
             row_count: rows,
             column_count: columns,
-            section_width: section_width,
-            section_height: section_height,
+            section_width,
+            section_height,
         }
     }
     pub fn new_grid(&self) -> Vec<Cell> {
         let mut result: Vec<Cell> = Vec::new();
-        for column in 0..self.column_count {
-            for row in 0..self.row_count {
+        for row in 0..self.row_count {
+            for column in 0..self.column_count {
                 let index = column + row * self.column_count;
                 result.push(Cell::new(
                     index,
@@ -72,8 +97,13 @@ impl GridDimensions {
         }
         result
     }
+    // sections tile the grid and every row, column and section holds each digit once
     pub fn is_valid(&self) -> bool {
-        (self.row_count % self.section_width == 0) && (self.column_count % self.section_height == 0)
+        let digit_count = self.get_section_size();
+        self.column_count.is_multiple_of(self.section_width)
+            && self.row_count.is_multiple_of(self.section_height)
+            && digit_count == self.column_count
+            && digit_count == self.row_count
     }
     pub fn get_row_count(&self) -> &usize {
         &self.row_count
@@ -91,10 +121,10 @@ impl GridDimensions {
         self.row_count * self.column_count
     }
     pub fn get_sections_in_row(&self) -> usize {
-        self.row_count / self.section_width
+        self.column_count / self.section_width
     }
     pub fn get_sections_in_column(&self) -> usize {
-        self.column_count / self.section_height
+        self.row_count / self.section_height
     }
     pub fn get_indices_for_row(&self, row: usize) -> Vec<usize> {
         if row < self.row_count {
@@ -123,7 +153,7 @@ impl GridDimensions {
             let first = 0usize;
             let last = self.row_count;
             let range: Vec<usize> = (first..last).collect();
-            range.iter().map(|x| x * self.row_count + column).collect()
+            range.iter().map(|x| x * self.column_count + column).collect()
         } else {
             Vec::new()
         }
@@ -142,10 +172,19 @@ impl GridDimensions {
         }
     }
     pub fn get_section_for_position(&self, column: usize, row: usize) -> usize {
-        row % self.section_width + (column % self.section_height) * self.get_sections_in_row()
+        (row / self.section_height) * self.get_sections_in_row() + column / self.section_width
     }
     pub fn get_data_size(&self) -> usize {
-        self.row_count*self.column_count
+        self.row_count * self.column_count
+    }
+    pub fn get_row_for_index(&self, index: usize) -> usize {
+        index / self.column_count
+    }
+    pub fn get_column_for_index(&self, index: usize) -> usize {
+        index % self.column_count
+    }
+    pub fn get_section_for_index(&self, index: usize) -> usize {
+        self.get_section_for_position(self.get_column_for_index(index), self.get_row_for_index(index))
     }
 }
 
@@ -176,4 +215,46 @@ mod tests {
         assert_eq!(data.get_indices_for_column(10).len(), 0);
         assert_eq!(data.get_indices_for_section(10).len(), 0);
     }
+    #[test]
+    fn test_cells_are_stored_by_index() {
+        let data = GridDimensions::new(9, 9, 3, 3);
+        let grid = data.new_grid();
+        for (position, cell) in grid.iter().enumerate() {
+            assert_eq!(cell.get_index(), &position);
+            assert_eq!(cell.get_row(), &data.get_row_for_index(position));
+            assert_eq!(cell.get_column(), &data.get_column_for_index(position));
+        }
+        assert_eq!(grid[30].get_name(), "r4c4");
+    }
+    #[test]
+    fn test_indices_with_sections_wider_than_high() {
+        let data = GridDimensions::new(6, 6, 3, 2);
+        assert_eq!(data.get_indices_for_column(4), vec![4, 10, 16, 22, 28, 34]);
+        assert_eq!(data.get_indices_for_section(1), vec![3, 4, 5, 9, 10, 11]);
+        assert_eq!(
+            data.get_indices_for_section(2),
+            vec![12, 13, 14, 18, 19, 20]
+        );
+        assert_eq!(data.get_section_for_position(4, 3), 3);
+        assert_eq!(data.get_section_for_position(2, 5), 4);
+        let grid = data.new_grid();
+        assert_eq!(grid[9].get_name(), "r2c4");
+        assert_eq!(grid[9].get_section(), &1);
+    }
+    #[test]
+    fn test_validity() {
+        assert!(GridDimensions::new(9, 9, 3, 3).is_valid());
+        assert!(GridDimensions::new(6, 6, 3, 2).is_valid());
+        assert!(!GridDimensions::new(6, 6, 2, 2).is_valid());
+        assert!(!GridDimensions::new(9, 6, 3, 2).is_valid());
+    }
+    #[test]
+    fn test_section_for_position_matches_section_indices() {
+        let data = GridDimensions::new(9, 9, 3, 3);
+        for section in 0..data.get_section_count() {
+            for index in data.get_indices_for_section(section) {
+                assert_eq!(data.get_section_for_index(index), section);
+            }
+        }
+    }
 }
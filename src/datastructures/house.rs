@@ -0,0 +1,288 @@
+// a group of cells that holds every digit at most once
+use super::constraint::Constraint;
+use super::digit::DigitHashSet;
+use super::griddimensions::GridDimensions;
+use super::sudoku::Sudoku;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HouseKind {
+    Row,
+    Column,
+    Section,
+    Diagonal,
+    // the extra 3x3 squares of a hyper sudoku and the groups of cells they imply
+    Window,
+    ImpliedWindow,
+    // the cells in the same place of every section
+    DisjointGroup,
+}
+
+impl HouseKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            HouseKind::Row => "row",
+            HouseKind::Column => "column",
+            HouseKind::Section => "section",
+            HouseKind::Diagonal => "diagonal",
+            HouseKind::Window => "window",
+            HouseKind::ImpliedWindow => "implied window",
+            HouseKind::DisjointGroup => "disjoint group",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct House {
+    kind: HouseKind,
+    // counted from zero within its kind
+    number: usize,
+    cells: Vec<usize>,
+}
+
+impl House {
+    pub fn new(kind: HouseKind, number: usize, cells: Vec<usize>) -> Self {
+        House {
+            kind,
+            number,
+            cells,
+        }
+    }
+    // the rows, columns and sections of a grid, in that order
+    pub fn standard_houses(grid_dimensions: &GridDimensions) -> Vec<House> {
+        let mut houses = Vec::new();
+        for row in 0..*grid_dimensions.get_row_count() {
+            houses.push(House::new(
+                HouseKind::Row,
+                row,
+                grid_dimensions.get_indices_for_row(row),
+            ));
+        }
+        for column in 0..*grid_dimensions.get_column_count() {
+            houses.push(House::new(
+                HouseKind::Column,
+                column,
+                grid_dimensions.get_indices_for_column(column),
+            ));
+        }
+        for section in 0..grid_dimensions.get_section_count() {
+            houses.push(House::new(
+                HouseKind::Section,
+                section,
+                grid_dimensions.get_indices_for_section(section),
+            ));
+        }
+        houses
+    }
+    // the main diagonal from the top left and the anti diagonal from the top right, only square
+    // grids have them
+    pub fn diagonals(grid_dimensions: &GridDimensions) -> Vec<House> {
+        let size = *grid_dimensions.get_row_count();
+        if size != *grid_dimensions.get_column_count() {
+            return Vec::new();
+        }
+        vec![
+            House::new(
+                HouseKind::Diagonal,
+                0,
+                (0..size).map(|i| i * size + i).collect(),
+            ),
+            House::new(
+                HouseKind::Diagonal,
+                1,
+                (0..size).map(|i| i * size + size - 1 - i).collect(),
+            ),
+        ]
+    }
+    // the windows of a hyper sudoku, one row and column in from every section corner and one apart,
+    // followed by the groups of the rows and columns they leave out, which hold every digit once as
+    // well; only grids of square sections that are as big as the sections are wide have them
+    pub fn windows(grid_dimensions: &GridDimensions) -> Vec<House> {
+        let size = *grid_dimensions.get_row_count();
+        let width = *grid_dimensions.get_section_width();
+        if size != *grid_dimensions.get_column_count()
+            || width != *grid_dimensions.get_section_height()
+            || width * width != size
+            || width < 2
+        {
+            return Vec::new();
+        }
+        let mut groups: Vec<Vec<usize>> = (0..width - 1)
+            .map(|i| (0..width).map(|j| 1 + i * (width + 1) + j).collect())
+            .collect();
+        let rest = (0..size)
+            .filter(|line| !groups.iter().any(|group| group.contains(line)))
+            .collect();
+        groups.push(rest);
+        let mut windows = Vec::new();
+        let mut implied = Vec::new();
+        for (row_position, rows) in groups.iter().enumerate() {
+            for (column_position, columns) in groups.iter().enumerate() {
+                let mut cells: Vec<usize> = rows
+                    .iter()
+                    .flat_map(|row| columns.iter().map(move |column| row * size + column))
+                    .collect();
+                cells.sort_unstable();
+                if row_position < width - 1 && column_position < width - 1 {
+                    windows.push(House::new(HouseKind::Window, windows.len(), cells));
+                } else {
+                    implied.push(House::new(HouseKind::ImpliedWindow, implied.len(), cells));
+                }
+            }
+        }
+        windows.extend(implied);
+        windows
+    }
+    // for every place in a section the cells in that place in all sections; only grids with as
+    // many sections as a section has cells have them
+    pub fn disjoint_groups(grid_dimensions: &GridDimensions) -> Vec<House> {
+        let size = grid_dimensions.get_section_size();
+        if grid_dimensions.get_section_count() != size {
+            return Vec::new();
+        }
+        let sections: Vec<Vec<usize>> = (0..size)
+            .map(|section| grid_dimensions.get_indices_for_section(section))
+            .collect();
+        (0..size)
+            .map(|place| {
+                let mut cells: Vec<usize> = sections.iter().map(|cells| cells[place]).collect();
+                cells.sort_unstable();
+                House::new(HouseKind::DisjointGroup, place, cells)
+            })
+            .collect()
+    }
+    // irregular sections for a jigsaw grid, regions holds the section of every cell; None unless
+    // every section is connected and as big as a row
+    pub fn regions(grid_dimensions: &GridDimensions, regions: &[usize]) -> Option<Vec<House>> {
+        let columns = *grid_dimensions.get_column_count();
+        let rows = *grid_dimensions.get_row_count();
+        if regions.len() != grid_dimensions.get_data_size() || columns != rows {
+            return None;
+        }
+        let mut houses = Vec::new();
+        for region in 0..columns {
+            let cells: Vec<usize> = (0..regions.len())
+                .filter(|i| regions[*i] == region)
+                .collect();
+            if cells.len() != columns || !is_connected(&cells, columns) {
+                return None;
+            }
+            houses.push(House::new(HouseKind::Section, region, cells));
+        }
+        Some(houses)
+    }
+    pub fn get_kind(&self) -> &HouseKind {
+        &self.kind
+    }
+    pub fn get_number(&self) -> &usize {
+        &self.number
+    }
+    pub fn get_cells(&self) -> &Vec<usize> {
+        &self.cells
+    }
+    pub fn contains(&self, index: usize) -> bool {
+        self.cells.contains(&index)
+    }
+    pub fn is_line(&self) -> bool {
+        matches!(self.kind, HouseKind::Row | HouseKind::Column)
+    }
+    // the digits placed in the house, None when one of them is placed twice
+    pub fn used_digits(&self, sudoku: &Sudoku) -> Option<DigitHashSet> {
+        let mut used = DigitHashSet::new();
+        for index in &self.cells {
+            if let Some(digit) = sudoku.get_cell(*index)?.get_value() {
+                if !used.insert(*digit) {
+                    return None;
+                }
+            }
+        }
+        Some(used)
+    }
+}
+
+impl Constraint for House {
+    fn get_name(&self) -> String {
+        format!("{} {}", self.kind.get_name(), self.number + 1)
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        self.used_digits(sudoku).is_some()
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let used = self.used_digits(sudoku).unwrap_or_default();
+        for index in &self.cells {
+            candidates[*index].retain(|digit| !used.contains(digit));
+        }
+    }
+    fn sees(&self, first: usize, second: usize) -> bool {
+        first != second && self.contains(first) && self.contains(second)
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+// whether the cells form one piece, cells touching on a side are connected
+fn is_connected(cells: &[usize], columns: usize) -> bool {
+    let mut reached = vec![cells[0]];
+    let mut position = 0;
+    while position < reached.len() {
+        let cell = reached[position];
+        position += 1;
+        for other in cells {
+            let touches = (cell / columns == other / columns && cell.abs_diff(*other) == 1)
+                || cell.abs_diff(*other) == columns;
+            if touches && !reached.contains(other) {
+                reached.push(*other);
+            }
+        }
+    }
+    reached.len() == cells.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::Digit;
+
+    #[test]
+    fn test_standard_houses() {
+        let houses = House::standard_houses(&GridDimensions::new(9, 9, 3, 3));
+        assert_eq!(houses.len(), 27);
+        assert_eq!(houses[9].get_kind(), &HouseKind::Column);
+        assert_eq!(houses[19].get_name(), "section 2");
+        assert!(houses[19].contains(5));
+    }
+    #[test]
+    fn test_regions() {
+        let dimensions = GridDimensions::new(4, 4, 2, 2);
+        let regions = [0, 0, 0, 1, 2, 0, 1, 1, 2, 2, 3, 1, 2, 3, 3, 3];
+        let houses = House::regions(&dimensions, &regions).unwrap();
+        assert_eq!(houses[1].get_cells(), &vec![3, 6, 7, 11]);
+        // the first section is in two pieces
+        let split = [0, 0, 1, 0, 2, 1, 0, 1, 2, 2, 3, 1, 2, 3, 3, 3];
+        assert!(House::regions(&dimensions, &split).is_none());
+        let uneven = [0, 0, 0, 0, 0, 1, 1, 1, 2, 2, 3, 1, 2, 3, 3, 3];
+        assert!(House::regions(&dimensions, &uneven).is_none());
+    }
+    #[test]
+    fn test_diagonals() {
+        let diagonals = House::diagonals(&GridDimensions::new(9, 9, 3, 3));
+        assert_eq!(diagonals[0].get_cells()[8], 80);
+        assert_eq!(diagonals[1].get_cells()[0], 8);
+        assert_eq!(diagonals[1].get_name(), "diagonal 2");
+        assert!(House::diagonals(&GridDimensions::new(6, 4, 3, 2)).is_empty());
+    }
+    #[test]
+    fn test_house_validity() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let row = sudoku.get_houses()[0].clone();
+        sudoku.get_mut_cell(0).unwrap().set_value(Some(Digit::One));
+        assert!(row.is_valid(&sudoku));
+        sudoku.get_mut_cell(8).unwrap().set_value(Some(Digit::One));
+        assert!(!row.is_valid(&sudoku));
+        assert!(row.sees(0, 8));
+        assert!(!row.sees(0, 9));
+    }
+}
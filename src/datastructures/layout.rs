@@ -0,0 +1,169 @@
+// puzzles made of several overlapping grids, such as samurai: every grid is placed on one big
+// canvas, cells outside all of the grids are not used
+use super::griddimensions::GridDimensions;
+use super::house::{House, HouseKind};
+
+#[derive(Debug, Clone)]
+pub struct Layout {
+    grid_dimensions: GridDimensions,
+    // the size of every grid, a row of sections by a column of sections
+    grid_size: usize,
+    // the top row and left column of every grid on the canvas
+    origins: Vec<(usize, usize)>,
+}
+
+impl Layout {
+    // None unless every grid fits on the canvas and lines up with its sections
+    pub fn new(
+        columns: usize,
+        rows: usize,
+        section_width: usize,
+        section_height: usize,
+        origins: Vec<(usize, usize)>,
+    ) -> Option<Self> {
+        let grid_size = section_width * section_height;
+        let fits = origins.iter().all(|(row, column)| {
+            row + grid_size <= rows
+                && column + grid_size <= columns
+                && row % section_height == 0
+                && column % section_width == 0
+        });
+        if !fits || origins.is_empty() || grid_size == 0 {
+            return None;
+        }
+        Some(Layout {
+            grid_dimensions: GridDimensions::new(columns, rows, section_width, section_height),
+            grid_size,
+            origins,
+        })
+    }
+    // five grids, the middle one shares a corner section with each of the others
+    pub fn samurai() -> Self {
+        Layout::new(
+            21,
+            21,
+            3,
+            3,
+            vec![(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)],
+        )
+        .unwrap()
+    }
+    // two grids sharing one corner section
+    pub fn twin() -> Self {
+        Layout::new(15, 15, 3, 3, vec![(0, 0), (6, 6)]).unwrap()
+    }
+    // four grids in a square, each overlapping its neighbours by two columns or rows of sections
+    pub fn butterfly() -> Self {
+        Layout::new(12, 12, 3, 3, vec![(0, 0), (0, 3), (3, 0), (3, 3)]).unwrap()
+    }
+    // a middle grid with four petals that overlap it by two rows or columns of sections
+    pub fn flower() -> Self {
+        Layout::new(15, 15, 3, 3, vec![(3, 3), (0, 3), (3, 0), (3, 6), (6, 3)]).unwrap()
+    }
+    pub fn get_grid_dimensions(&self) -> &GridDimensions {
+        &self.grid_dimensions
+    }
+    pub fn get_grid_size(&self) -> &usize {
+        &self.grid_size
+    }
+    pub fn get_origins(&self) -> &Vec<(usize, usize)> {
+        &self.origins
+    }
+    // the canvas indices of a grid, row by row
+    pub fn grid_cells(&self, grid: usize) -> Vec<usize> {
+        let (top, left) = match self.origins.get(grid) {
+            Some(origin) => *origin,
+            None => return Vec::new(),
+        };
+        let columns = *self.grid_dimensions.get_column_count();
+        let mut cells = Vec::new();
+        for row in top..top + self.grid_size {
+            for column in left..left + self.grid_size {
+                cells.push(row * columns + column);
+            }
+        }
+        cells
+    }
+    // for every cell of the canvas whether it is part of a grid
+    pub fn active_cells(&self) -> Vec<bool> {
+        let mut active = vec![false; self.grid_dimensions.get_data_size()];
+        for grid in 0..self.origins.len() {
+            for index in self.grid_cells(grid) {
+                active[index] = true;
+            }
+        }
+        active
+    }
+    // the rows, columns and sections of every grid, a section shared by grids only once; rows
+    // and columns are numbered in order, sections by their place on the canvas
+    pub fn houses(&self) -> Vec<House> {
+        let mut rows: Vec<Vec<usize>> = Vec::new();
+        let mut columns: Vec<Vec<usize>> = Vec::new();
+        let mut sections: Vec<usize> = Vec::new();
+        for grid in 0..self.origins.len() {
+            let cells = self.grid_cells(grid);
+            for line in 0..self.grid_size {
+                let row = cells[line * self.grid_size..(line + 1) * self.grid_size].to_vec();
+                let column: Vec<usize> = (0..self.grid_size)
+                    .map(|i| cells[i * self.grid_size + line])
+                    .collect();
+                if !rows.contains(&row) {
+                    rows.push(row);
+                }
+                if !columns.contains(&column) {
+                    columns.push(column);
+                }
+            }
+            for index in cells {
+                let section = self.grid_dimensions.get_section_for_index(index);
+                if !sections.contains(&section) {
+                    sections.push(section);
+                }
+            }
+        }
+        sections.sort_unstable();
+        let mut houses: Vec<House> = Vec::new();
+        for (number, cells) in rows.into_iter().enumerate() {
+            houses.push(House::new(HouseKind::Row, number, cells));
+        }
+        for (number, cells) in columns.into_iter().enumerate() {
+            houses.push(House::new(HouseKind::Column, number, cells));
+        }
+        for section in sections {
+            let cells = self.grid_dimensions.get_indices_for_section(section);
+            houses.push(House::new(HouseKind::Section, section, cells));
+        }
+        houses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layouts() {
+        let samurai = Layout::samurai();
+        let active = samurai.active_cells();
+        assert_eq!(active.iter().filter(|a| **a).count(), 5 * 81 - 4 * 9);
+        assert!(!active[9]);
+        // the middle grid starts at r7c7 and shares its corners
+        assert_eq!(samurai.grid_cells(2)[0], 6 * 21 + 6);
+        assert_eq!(samurai.houses().len(), 5 * 27 - 4);
+        let butterfly = Layout::butterfly();
+        assert!(butterfly.active_cells().iter().all(|a| *a));
+        // every row of the canvas is split in two overlapping rows of a grid, columns as well
+        assert_eq!(butterfly.houses().len(), 2 * 12 + 2 * 12 + 16);
+        assert_eq!(Layout::twin().houses().len(), 2 * 27 - 1);
+        assert_eq!(
+            Layout::flower()
+                .active_cells()
+                .iter()
+                .filter(|a| **a)
+                .count(),
+            225 - 4 * 9
+        );
+        assert!(Layout::new(9, 9, 3, 3, vec![(1, 0)]).is_none());
+        assert!(Layout::new(9, 9, 3, 3, vec![(0, 3)]).is_none());
+    }
+}
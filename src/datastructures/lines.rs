@@ -0,0 +1,341 @@
+// rules along a path of cells: thermometers, arrows and palindromes; the cells are given in order,
+// from the bulb of a thermometer and from the circle of an arrow
+use super::constraint::{options, value_at, Constraint, Marking};
+use super::digit::DigitHashSet;
+use super::sudoku::Sudoku;
+
+fn retain_values(sudoku: &Sudoku, candidates: &mut [DigitHashSet], index: usize, values: &[usize]) {
+    if sudoku.get_cell(index).is_some_and(|cell| cell.is_empty()) {
+        candidates[index].retain(|digit| values.contains(&digit.to_usize()));
+    }
+}
+
+// digits rise strictly from the bulb to the tip
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thermometer {
+    cells: Vec<usize>,
+}
+
+impl Thermometer {
+    pub fn new(cells: Vec<usize>) -> Self {
+        Thermometer { cells }
+    }
+    pub fn get_bulb(&self) -> Option<&usize> {
+        self.cells.first()
+    }
+}
+
+impl Constraint for Thermometer {
+    fn get_name(&self) -> String {
+        String::from("thermometer")
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+    // two placed digits need room for the cells between them, and every digit for the cells
+    // before and after it
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        let largest = sudoku.get_digits().len();
+        let placed: Vec<(usize, usize)> = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter_map(|(position, index)| value_at(sudoku, *index).map(|v| (position, v)))
+            .collect();
+        placed.iter().all(|(position, value)| {
+            *value > *position && value + (self.cells.len() - 1 - position) <= largest
+        }) && placed
+            .windows(2)
+            .all(|pair| pair[1].1 >= pair[0].1 + (pair[1].0 - pair[0].0))
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let all: Vec<Vec<usize>> = self
+            .cells
+            .iter()
+            .map(|index| options(sudoku, candidates, *index))
+            .collect();
+        let count = self.cells.len();
+        let mut lowest = vec![0usize; count];
+        let mut highest = vec![usize::MAX; count];
+        for position in 0..count {
+            let floor = if position == 0 {
+                1
+            } else {
+                lowest[position - 1] + 1
+            };
+            lowest[position] = all[position]
+                .iter()
+                .copied()
+                .find(|v| *v >= floor)
+                .unwrap_or(usize::MAX - count);
+        }
+        for position in (0..count).rev() {
+            let ceiling = if position + 1 == count {
+                usize::MAX
+            } else {
+                highest[position + 1].saturating_sub(1)
+            };
+            highest[position] = all[position]
+                .iter()
+                .copied()
+                .filter(|v| *v <= ceiling)
+                .max()
+                .unwrap_or(0);
+        }
+        for (position, index) in self.cells.iter().enumerate() {
+            let values: Vec<usize> = all[position]
+                .iter()
+                .copied()
+                .filter(|v| lowest[position] <= *v && *v <= highest[position])
+                .collect();
+            retain_values(sudoku, candidates, *index, &values);
+        }
+    }
+    fn get_markings(&self) -> Vec<Marking> {
+        let mut markings = vec![Marking::Line {
+            cells: self.cells.clone(),
+            color: "lightgray",
+        }];
+        if let Some(bulb) = self.get_bulb() {
+            markings.push(Marking::Circle {
+                cell: *bulb,
+                filled: true,
+            });
+        }
+        markings
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+// the digits along the arrow add up to the digit in its circle, they may repeat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arrow {
+    circle: usize,
+    path: Vec<usize>,
+}
+
+// whether the cells after the first few chosen ones can be filled from their options to add up
+// to rest, cells that see each other never sharing a digit
+fn fill_arrow(
+    sudoku: &Sudoku,
+    cells: &[usize],
+    lists: &[Vec<usize>],
+    chosen: &mut Vec<usize>,
+    rest: usize,
+) -> bool {
+    let position = chosen.len();
+    if position == cells.len() {
+        return rest == 0;
+    }
+    // every cell left adds at least one
+    if rest < cells.len() - position {
+        return false;
+    }
+    for value in &lists[position] {
+        if *value > rest
+            || (0..position)
+                .any(|other| chosen[other] == *value && sudoku.sees(cells[other], cells[position]))
+        {
+            continue;
+        }
+        chosen.push(*value);
+        let found = fill_arrow(sudoku, cells, lists, chosen, rest - value);
+        chosen.pop();
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+impl Arrow {
+    pub fn new(circle: usize, path: Vec<usize>) -> Self {
+        Arrow { circle, path }
+    }
+    pub fn get_circle(&self) -> &usize {
+        &self.circle
+    }
+    pub fn get_path(&self) -> &Vec<usize> {
+        &self.path
+    }
+}
+
+impl Constraint for Arrow {
+    fn get_name(&self) -> String {
+        String::from("arrow")
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        let mut cells = vec![self.circle];
+        cells.extend(self.path.iter().copied());
+        cells
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        let largest = sudoku.get_digits().len();
+        let placed: Vec<usize> = self
+            .path
+            .iter()
+            .filter_map(|index| value_at(sudoku, *index))
+            .collect();
+        // every empty cell on the arrow adds at least one
+        let least = placed.iter().sum::<usize>() + self.path.len() - placed.len();
+        match value_at(sudoku, self.circle) {
+            Some(circle) if placed.len() == self.path.len() => least == circle,
+            Some(circle) => {
+                least <= circle
+                    && placed.iter().sum::<usize>() + (self.path.len() - placed.len()) * largest
+                        >= circle
+            }
+            None => least <= largest,
+        }
+    }
+    // a digit stays when some filling of the arrow and its circle uses it
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let cells = self.get_cells();
+        let lists: Vec<Vec<usize>> = cells
+            .iter()
+            .map(|index| options(sudoku, candidates, *index))
+            .collect();
+        for (position, index) in cells.iter().enumerate() {
+            let values: Vec<usize> = lists[position]
+                .iter()
+                .copied()
+                .filter(|value| {
+                    let mut fixed = lists.clone();
+                    fixed[position] = vec![*value];
+                    fixed[0].iter().any(|circle| {
+                        let mut order = vec![*circle];
+                        fill_arrow(sudoku, &cells, &fixed, &mut order, *circle)
+                    })
+                })
+                .collect();
+            retain_values(sudoku, candidates, *index, &values);
+        }
+    }
+    fn get_markings(&self) -> Vec<Marking> {
+        vec![
+            Marking::Arrow {
+                cells: self.get_cells(),
+            },
+            Marking::Circle {
+                cell: self.circle,
+                filled: false,
+            },
+        ]
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+// the digits read the same from both ends of the line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palindrome {
+    cells: Vec<usize>,
+}
+
+impl Palindrome {
+    pub fn new(cells: Vec<usize>) -> Self {
+        Palindrome { cells }
+    }
+    // the cells that must hold the same digit
+    fn pairs(&self) -> Vec<(usize, usize)> {
+        let count = self.cells.len();
+        (0..count / 2)
+            .map(|i| (self.cells[i], self.cells[count - 1 - i]))
+            .collect()
+    }
+}
+
+impl Constraint for Palindrome {
+    fn get_name(&self) -> String {
+        String::from("palindrome")
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        self.pairs().iter().all(|(first, second)| {
+            match (value_at(sudoku, *first), value_at(sudoku, *second)) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+        })
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        for (first, second) in self.pairs() {
+            let shared: Vec<usize> = options(sudoku, candidates, first)
+                .into_iter()
+                .filter(|value| options(sudoku, candidates, second).contains(value))
+                .collect();
+            retain_values(sudoku, candidates, first, &shared);
+            retain_values(sudoku, candidates, second, &shared);
+        }
+    }
+    fn get_markings(&self) -> Vec<Marking> {
+        vec![Marking::Line {
+            cells: self.cells.clone(),
+            color: "plum",
+        }]
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::constraint::candidate_values as values;
+    use crate::datastructures::digit::Digit::*;
+
+    #[test]
+    fn test_thermometer() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(Thermometer::new(vec![0, 1, 2, 11])));
+        assert_eq!(values(&sudoku, 0), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(values(&sudoku, 11), vec![4, 5, 6, 7, 8, 9]);
+        sudoku.set_digit(1, Five);
+        assert_eq!(values(&sudoku, 0), vec![1, 2, 3, 4]);
+        assert_eq!(values(&sudoku, 2), vec![6, 7, 8]);
+        sudoku.get_mut_cell(11).unwrap().set_value(Some(Six));
+        assert!(!sudoku.is_consistent());
+    }
+    #[test]
+    fn test_arrow() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(Arrow::new(0, vec![10, 20])));
+        // two cells in a section add up to three at least
+        assert_eq!(values(&sudoku, 0), vec![3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(values(&sudoku, 10), (1..=8).collect::<Vec<usize>>());
+        sudoku.set_digit(0, Four);
+        assert_eq!(values(&sudoku, 10), vec![1, 3]);
+        let solution = sudoku.get_solution().unwrap();
+        let sum: usize = [10, 20]
+            .iter()
+            .map(|i| {
+                solution
+                    .get_cell(*i)
+                    .unwrap()
+                    .get_value()
+                    .unwrap()
+                    .to_usize()
+            })
+            .sum();
+        assert_eq!(sum, 4);
+    }
+    #[test]
+    fn test_palindrome() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(Palindrome::new(vec![0, 10, 20, 30, 40])));
+        sudoku.set_digit(0, Seven);
+        assert_eq!(values(&sudoku, 40), vec![7]);
+        assert_eq!(values(&sudoku, 30), values(&sudoku, 10));
+        let solution = sudoku.get_solution().unwrap();
+        assert_eq!(
+            solution.get_cell(10).unwrap().get_value(),
+            solution.get_cell(30).unwrap().get_value()
+        );
+    }
+}
@@ -1,7 +1,17 @@
+pub mod cage;
+pub mod chess;
+pub mod comparison;
+pub mod constraint;
 pub mod digit;
+pub mod edges;
 pub mod griddimensions;
+pub mod house;
+pub mod layout;
+pub mod lines;
+pub mod outside;
 // pub mod row;
 // pub mod column;
 // pub mod section;
+pub mod solver;
 pub mod sudoku;
 pub mod and_intersect;
\ No newline at end of file
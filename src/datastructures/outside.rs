@@ -0,0 +1,497 @@
+// clues outside the grid about the cells in one direction from them: sandwich sums between the
+// lowest and highest digit of a line, skyscraper counts of digits seen from the edge, and little
+// killer sums along a diagonal
+use super::cage::Cage;
+use super::constraint::{options, value_at, Constraint, Marking};
+use super::digit::DigitHashSet;
+use super::griddimensions::GridDimensions;
+use super::sudoku::Sudoku;
+
+// skyscraper lines with more empty cells than this are only pruned by the distance to the clue
+const SKYSCRAPER_SEARCH_LIMIT: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutsideKind {
+    Sandwich,
+    Skyscraper,
+    LittleKiller,
+}
+
+impl OutsideKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            OutsideKind::Sandwich => "sandwich",
+            OutsideKind::Skyscraper => "skyscraper",
+            OutsideKind::LittleKiller => "little killer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutsideClue {
+    kind: OutsideKind,
+    value: usize,
+    // the row and column of the clue, one step outside the grid
+    position: (isize, isize),
+    // the row and column step from the clue into the grid
+    direction: (isize, isize),
+    // the cells from the clue onwards
+    cells: Vec<usize>,
+}
+
+// whether one value of every list, all different, adds up to target
+fn fill_distinct(lists: &[Vec<usize>], target: usize, used: &mut Vec<usize>) -> bool {
+    let (first, rest) = match lists.split_first() {
+        Some(split) => split,
+        None => return target == 0,
+    };
+    for value in first {
+        if *value > target || used.contains(value) {
+            continue;
+        }
+        used.push(*value);
+        let found = fill_distinct(rest, target - value, used);
+        used.pop();
+        if found {
+            return true;
+        }
+    }
+    false
+}
+
+// the line from the clue on a side of the grid: top, bottom, left or right, number counted from
+// zero along that side
+fn side_position(
+    grid_dimensions: &GridDimensions,
+    side: &str,
+    number: usize,
+) -> Option<((isize, isize), (isize, isize))> {
+    let rows = *grid_dimensions.get_row_count() as isize;
+    let columns = *grid_dimensions.get_column_count() as isize;
+    let number = number as isize;
+    match side {
+        "top" => Some(((-1, number), (1, 0))),
+        "bottom" => Some(((rows, number), (-1, 0))),
+        "left" => Some(((number, -1), (0, 1))),
+        "right" => Some(((number, columns), (0, -1))),
+        _ => None,
+    }
+}
+
+impl OutsideClue {
+    // None when the clue is inside the grid or does not look at any cell of it
+    pub fn new(
+        kind: OutsideKind,
+        grid_dimensions: &GridDimensions,
+        position: (isize, isize),
+        direction: (isize, isize),
+        value: usize,
+    ) -> Option<Self> {
+        let rows = *grid_dimensions.get_row_count() as isize;
+        let columns = *grid_dimensions.get_column_count() as isize;
+        let inside = |(row, column): (isize, isize)| {
+            row >= 0 && column >= 0 && row < rows && column < columns
+        };
+        let diagonal = direction.0 != 0 && direction.1 != 0;
+        if inside(position)
+            || direction.0.abs() > 1
+            || direction.1.abs() > 1
+            || direction == (0, 0)
+            || diagonal != (kind == OutsideKind::LittleKiller)
+        {
+            return None;
+        }
+        let mut cells = Vec::new();
+        let mut current = (position.0 + direction.0, position.1 + direction.1);
+        while inside(current) {
+            cells.push((current.0 * columns + current.1) as usize);
+            current = (current.0 + direction.0, current.1 + direction.1);
+        }
+        if cells.is_empty() {
+            return None;
+        }
+        Some(OutsideClue {
+            kind,
+            value,
+            position,
+            direction,
+            cells,
+        })
+    }
+    // a sandwich or skyscraper clue on a side of the grid, number counted from zero
+    pub fn on_side(
+        kind: OutsideKind,
+        grid_dimensions: &GridDimensions,
+        side: &str,
+        number: usize,
+        value: usize,
+    ) -> Option<Self> {
+        let (position, direction) = side_position(grid_dimensions, side, number)?;
+        OutsideClue::new(kind, grid_dimensions, position, direction, value)
+    }
+    // reads one clue, such as "sandwich top 3 15", "skyscraper right 1 4" or
+    // "little-killer left 2 up-right 23"; rows and columns are counted from one
+    pub fn parse(grid_dimensions: &GridDimensions, text: &str) -> Option<Self> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let kind = match *words.first()? {
+            "sandwich" => OutsideKind::Sandwich,
+            "skyscraper" => OutsideKind::Skyscraper,
+            "little-killer" => OutsideKind::LittleKiller,
+            _ => return None,
+        };
+        let expected = if kind == OutsideKind::LittleKiller {
+            5
+        } else {
+            4
+        };
+        if words.len() != expected {
+            return None;
+        }
+        let number = words[2].parse::<usize>().ok()?.checked_sub(1)?;
+        let value = words[expected - 1].parse::<usize>().ok()?;
+        let (position, mut direction) = side_position(grid_dimensions, words[1], number)?;
+        if kind == OutsideKind::LittleKiller {
+            direction = match words[3] {
+                "up-left" => (-1, -1),
+                "up-right" => (-1, 1),
+                "down-left" => (1, -1),
+                "down-right" => (1, 1),
+                _ => return None,
+            };
+        }
+        OutsideClue::new(kind, grid_dimensions, position, direction, value)
+    }
+    pub fn get_kind(&self) -> &OutsideKind {
+        &self.kind
+    }
+    pub fn get_value(&self) -> &usize {
+        &self.value
+    }
+    pub fn get_position(&self) -> &(isize, isize) {
+        &self.position
+    }
+    pub fn get_direction(&self) -> &(isize, isize) {
+        &self.direction
+    }
+    // the digits along a little killer diagonal may repeat
+    fn diagonal(&self) -> Cage {
+        Cage::new_with_repeats(self.cells.clone(), self.value)
+    }
+
+    fn sandwich_is_valid(&self, sudoku: &Sudoku) -> bool {
+        let largest = sudoku.get_digits().len();
+        let values: Vec<Option<usize>> = self.cells.iter().map(|i| value_at(sudoku, *i)).collect();
+        let lowest = values.iter().position(|v| *v == Some(1));
+        let highest = values.iter().position(|v| *v == Some(largest));
+        let (first, last) = match (lowest, highest) {
+            (Some(a), Some(b)) => (a.min(b), a.max(b)),
+            _ => return true,
+        };
+        let between = &values[first + 1..last];
+        let placed: usize = between.iter().flatten().sum();
+        let empty = between.iter().filter(|v| v.is_none()).count();
+        if empty == 0 {
+            return placed == self.value;
+        }
+        placed + empty * 2 <= self.value && placed + empty * (largest - 1) >= self.value
+    }
+    fn sandwich_restrict(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let largest = sudoku.get_digits().len();
+        let lists: Vec<Vec<usize>> = self
+            .cells
+            .iter()
+            .map(|i| options(sudoku, candidates, *i))
+            .collect();
+        let count = lists.len();
+        let mut supported: Vec<Vec<usize>> = vec![Vec::new(); count];
+        for low in (0..count).filter(|i| lists[*i].contains(&1)) {
+            for high in (0..count).filter(|i| *i != low && lists[*i].contains(&largest)) {
+                let (first, last) = (low.min(high), low.max(high));
+                let inner: Vec<Vec<usize>> = lists
+                    .iter()
+                    .enumerate()
+                    .map(|(position, list)| {
+                        list.iter()
+                            .copied()
+                            .filter(|v| *v != 1 && *v != largest)
+                            .filter(|_| position != low && position != high)
+                            .collect()
+                    })
+                    .collect();
+                // the crust holds the only one and the only highest digit of the line
+                if (0..count).any(|p| p != low && p != high && inner[p].is_empty()) {
+                    continue;
+                }
+                let between = &inner[first + 1..last];
+                if !fill_distinct(between, self.value, &mut Vec::new()) {
+                    continue;
+                }
+                supported[low].push(1);
+                supported[high].push(largest);
+                for position in 0..count {
+                    if position == low || position == high {
+                        continue;
+                    }
+                    if position < first || position > last {
+                        supported[position].extend(inner[position].iter().copied());
+                        continue;
+                    }
+                    for value in &inner[position] {
+                        let mut fixed = between.to_vec();
+                        fixed[position - first - 1] = vec![*value];
+                        if fill_distinct(&fixed, self.value, &mut Vec::new()) {
+                            supported[position].push(*value);
+                        }
+                    }
+                }
+            }
+        }
+        for (position, index) in self.cells.iter().enumerate() {
+            if sudoku.get_cell(*index).is_some_and(|cell| cell.is_empty()) {
+                candidates[*index].retain(|d| supported[position].contains(&d.to_usize()));
+            }
+        }
+    }
+
+    fn skyscraper_is_valid(&self, sudoku: &Sudoku) -> bool {
+        let largest = sudoku.get_digits().len();
+        let values: Vec<Option<usize>> = self.cells.iter().map(|i| value_at(sudoku, *i)).collect();
+        let mut visible = 0;
+        let mut highest = 0;
+        let mut filled = 0;
+        for value in values.iter().take_while(|v| v.is_some()).flatten() {
+            filled += 1;
+            if *value > highest {
+                visible += 1;
+                highest = *value;
+            }
+        }
+        if filled == values.len() {
+            return visible == self.value;
+        }
+        // the highest digit hides everything behind it
+        if let Some(position) = values.iter().position(|v| *v == Some(largest)) {
+            if position + 1 < self.value {
+                return false;
+            }
+        }
+        // only digits taller than the ones so far can still be seen
+        let taller = (highest + 1..=largest)
+            .filter(|digit| !values.contains(&Some(*digit)))
+            .count()
+            + values[filled..]
+                .iter()
+                .filter(|v| v.is_some_and(|v| v > highest))
+                .count();
+        let more = taller.min(values.len() - filled);
+        // the highest digit is still to come and will be seen
+        let least = if highest == largest || values.len() != largest {
+            visible
+        } else {
+            visible + 1
+        };
+        least <= self.value && visible + more >= self.value
+    }
+    // tries every order of the rest of the line, keeping the digits some order with the right
+    // count uses
+    fn skyscraper_search(
+        &self,
+        lists: &[Vec<usize>],
+        position: usize,
+        highest: usize,
+        visible: usize,
+        chosen: &mut Vec<usize>,
+        supported: &mut [Vec<usize>],
+    ) -> bool {
+        if visible > self.value {
+            return false;
+        }
+        if position == lists.len() {
+            if visible != self.value {
+                return false;
+            }
+            for (place, value) in chosen.iter().enumerate() {
+                if !supported[place].contains(value) {
+                    supported[place].push(*value);
+                }
+            }
+            return true;
+        }
+        let mut found = false;
+        for value in &lists[position] {
+            if chosen.contains(value) {
+                continue;
+            }
+            chosen.push(*value);
+            let seen = if *value > highest {
+                visible + 1
+            } else {
+                visible
+            };
+            found |= self.skyscraper_search(
+                lists,
+                position + 1,
+                highest.max(*value),
+                seen,
+                chosen,
+                supported,
+            );
+            chosen.pop();
+        }
+        found
+    }
+    fn skyscraper_restrict(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        let largest = sudoku.get_digits().len();
+        let lists: Vec<Vec<usize>> = self
+            .cells
+            .iter()
+            .map(|i| options(sudoku, candidates, *i))
+            .collect();
+        let empty = self
+            .cells
+            .iter()
+            .filter(|i| sudoku.get_cell(**i).is_some_and(|cell| cell.is_empty()))
+            .count();
+        let mut supported: Vec<Vec<usize>> = vec![Vec::new(); lists.len()];
+        if empty <= SKYSCRAPER_SEARCH_LIMIT && lists.len() == largest {
+            self.skyscraper_search(&lists, 0, 0, 0, &mut Vec::new(), &mut supported);
+        } else {
+            // a digit needs taller ones behind it for the rest of the count
+            for (position, list) in lists.iter().enumerate() {
+                supported[position] = list
+                    .iter()
+                    .copied()
+                    .filter(|v| {
+                        *v + self.value <= largest + 1 + position
+                            && (self.value != 1 || position != 0 || *v == largest)
+                    })
+                    .collect();
+            }
+        }
+        for (position, index) in self.cells.iter().enumerate() {
+            if sudoku.get_cell(*index).is_some_and(|cell| cell.is_empty()) {
+                candidates[*index].retain(|d| supported[position].contains(&d.to_usize()));
+            }
+        }
+    }
+}
+
+impl Constraint for OutsideClue {
+    fn get_name(&self) -> String {
+        format!("{} {}", self.kind.get_name(), self.value)
+    }
+    fn get_cells(&self) -> Vec<usize> {
+        self.cells.clone()
+    }
+    fn is_valid(&self, sudoku: &Sudoku) -> bool {
+        match self.kind {
+            OutsideKind::Sandwich => self.sandwich_is_valid(sudoku),
+            OutsideKind::Skyscraper => self.skyscraper_is_valid(sudoku),
+            OutsideKind::LittleKiller => self.diagonal().is_valid(sudoku),
+        }
+    }
+    fn restrict_candidates(&self, sudoku: &Sudoku, candidates: &mut [DigitHashSet]) {
+        match self.kind {
+            OutsideKind::Sandwich => self.sandwich_restrict(sudoku, candidates),
+            OutsideKind::Skyscraper => self.skyscraper_restrict(sudoku, candidates),
+            OutsideKind::LittleKiller => self.diagonal().restrict_candidates(sudoku, candidates),
+        }
+    }
+    fn get_markings(&self) -> Vec<Marking> {
+        let direction = if self.kind == OutsideKind::LittleKiller {
+            Some(self.direction)
+        } else {
+            None
+        };
+        vec![Marking::Outside {
+            position: self.position,
+            text: self.value.to_string(),
+            direction,
+        }]
+    }
+    fn box_clone(&self) -> Box<dyn Constraint> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::constraint::candidate_values as values;
+    use crate::datastructures::digit::Digit::*;
+
+    #[test]
+    fn test_parse_clues() {
+        let dimensions = GridDimensions::new(9, 9, 3, 3);
+        let clue = OutsideClue::parse(&dimensions, "sandwich top 3 15").unwrap();
+        assert_eq!(clue.get_cells()[..2], [2, 11]);
+        let clue = OutsideClue::parse(&dimensions, "skyscraper right 1 4").unwrap();
+        assert_eq!(clue.get_cells()[0], 8);
+        let clue = OutsideClue::parse(&dimensions, "little-killer left 3 up-right 10").unwrap();
+        assert_eq!(clue.get_cells(), vec![9, 1]);
+        assert!(OutsideClue::parse(&dimensions, "little-killer left 2 up 10").is_none());
+        assert!(OutsideClue::parse(&dimensions, "sandwich top 10 15").is_none());
+        assert!(OutsideClue::parse(&dimensions, "skyscraper top 1").is_none());
+    }
+    #[test]
+    fn test_sandwich() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let clue = OutsideClue::on_side(
+            OutsideKind::Sandwich,
+            sudoku.get_grid_dimensions(),
+            "left",
+            0,
+            35,
+        );
+        sudoku.add_constraint(Box::new(clue.unwrap()));
+        // 35 takes every digit from 2 to 8, so the crust is at both ends
+        assert_eq!(values(&sudoku, 0), vec![1, 9]);
+        assert_eq!(values(&sudoku, 4), (2..=8).collect::<Vec<usize>>());
+        sudoku.set_digit(0, One);
+        assert_eq!(values(&sudoku, 8), vec![9]);
+        let solution = sudoku.get_solution().unwrap();
+        let sum: usize = (1..8)
+            .map(|i| {
+                solution
+                    .get_cell(i)
+                    .unwrap()
+                    .get_value()
+                    .unwrap()
+                    .to_usize()
+            })
+            .sum();
+        assert_eq!(sum, 35);
+    }
+    #[test]
+    fn test_skyscraper() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        let one = OutsideClue::on_side(OutsideKind::Skyscraper, &dimensions, "top", 0, 1).unwrap();
+        let four =
+            OutsideClue::on_side(OutsideKind::Skyscraper, &dimensions, "left", 1, 4).unwrap();
+        sudoku.add_constraint(Box::new(one));
+        sudoku.add_constraint(Box::new(four));
+        assert_eq!(values(&sudoku, 0), vec![9]);
+        // four visible: the first cell is at most six
+        assert_eq!(values(&sudoku, 9), (1..=6).collect::<Vec<usize>>());
+        let solution = sudoku.get_solution().unwrap();
+        assert!(solution.is_solved());
+        let mut broken = solution.clone();
+        broken.get_mut_cell(0).unwrap().set_value(None);
+        broken.get_mut_cell(9).unwrap().set_value(Some(Nine));
+        assert!(!broken.is_consistent());
+    }
+    #[test]
+    fn test_little_killer() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let clue = OutsideClue::parse(
+            sudoku.get_grid_dimensions(),
+            "little-killer top 7 down-right 5",
+        );
+        sudoku.add_constraint(Box::new(clue.unwrap()));
+        // r1c8, r2c9 add up to five and may not repeat as they share a section
+        assert_eq!(values(&sudoku, 7), vec![1, 2, 3, 4]);
+        sudoku.set_digit(7, Two);
+        assert_eq!(values(&sudoku, 17), vec![3]);
+    }
+}
@@ -0,0 +1,236 @@
+// brute force search used for solving and for counting solutions
+// digits are kept as bit masks while searching, bit 0 is digit one
+use super::digit::Digit;
+use super::sudoku::Sudoku;
+
+struct Search<'a> {
+    work: Sudoku,
+    peers: Vec<Vec<usize>>,
+    // for every cell the positions of the variant rules on it, houses are covered by the peers
+    rules: Vec<Vec<usize>>,
+    digits: Vec<Digit>,
+    // the digits to try for every cell, in order
+    orders: Vec<Vec<Digit>>,
+    limit: usize,
+    count: usize,
+    first: Option<Sudoku>,
+    // shown every solution as it is found, the grid is only good until it returns
+    visit: Option<&'a mut dyn FnMut(&Sudoku)>,
+}
+
+fn mask_for_digit(digit: Digit) -> u16 {
+    1 << (digit.to_usize() - 1)
+}
+
+impl<'a> Search<'a> {
+    fn new(sudoku: &Sudoku, limit: usize) -> Self {
+        let cell_count = sudoku.get_grid_dimensions().get_data_size();
+        Search {
+            work: sudoku.clone(),
+            peers: (0..cell_count).map(|i| sudoku.get_peers(i)).collect(),
+            rules: (0..cell_count)
+                .map(|i| {
+                    let constraints = sudoku.get_constraints();
+                    (0..constraints.len())
+                        .filter(|c| {
+                            !constraints[*c].is_pairwise()
+                                && constraints[*c].get_cells().contains(&i)
+                        })
+                        .collect()
+                })
+                .collect(),
+            digits: sudoku.get_digits(),
+            orders: vec![sudoku.get_digits(); cell_count],
+            limit,
+            count: 0,
+            first: None,
+            visit: None,
+        }
+    }
+
+    // variant rules such as cage sums are tried digit by digit so that they count when picking
+    // the next cell
+    fn available(&mut self, index: usize) -> u16 {
+        let mut used = 0u16;
+        for peer in &self.peers[index] {
+            if let Some(digit) = self.work.get_cell(*peer).unwrap().get_value() {
+                used |= mask_for_digit(*digit);
+            }
+        }
+        let mut available = 0u16;
+        for digit in self.digits.clone() {
+            let mask = mask_for_digit(digit);
+            if used & mask != 0 {
+                continue;
+            }
+            if !self.rules[index].is_empty() {
+                self.work
+                    .get_mut_cell(index)
+                    .unwrap()
+                    .set_value(Some(digit));
+                let fits = self.fits_rules(index);
+                self.work.get_mut_cell(index).unwrap().set_value(None);
+                if !fits {
+                    continue;
+                }
+            }
+            available |= mask;
+        }
+        available
+    }
+
+    // the empty cell with the fewest possibilities, None when the grid is full
+    fn most_constrained_cell(&mut self) -> Option<(usize, u16)> {
+        let mut best: Option<(usize, u16)> = None;
+        let empty: Vec<usize> = self
+            .work
+            .cells()
+            .filter(|c| c.is_empty())
+            .map(|c| *c.get_index())
+            .collect();
+        for index in empty {
+            let available = self.available(index);
+            let better = match best {
+                Some((_, mask)) => available.count_ones() < mask.count_ones(),
+                None => true,
+            };
+            if better {
+                best = Some((index, available));
+                if available.count_ones() <= 1 {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    fn fits_rules(&self, index: usize) -> bool {
+        let constraints = self.work.get_constraints();
+        self.rules[index]
+            .iter()
+            .all(|rule| constraints[*rule].is_valid(&self.work))
+    }
+
+    fn run(&mut self) {
+        let (index, available) = match self.most_constrained_cell() {
+            Some(found) => found,
+            None => {
+                self.count += 1;
+                match self.visit.as_mut() {
+                    Some(visit) => visit(&self.work),
+                    None if self.first.is_none() => {
+                        let mut solution = self.work.clone();
+                        solution.reset_candidates();
+                        self.first = Some(solution);
+                    }
+                    None => (),
+                }
+                return;
+            }
+        };
+        for digit in self.orders[index].clone() {
+            if available & mask_for_digit(digit) == 0 {
+                continue;
+            }
+            self.work
+                .get_mut_cell(index)
+                .unwrap()
+                .set_value(Some(digit));
+            self.run();
+            self.work.get_mut_cell(index).unwrap().set_value(None);
+            if self.count >= self.limit {
+                return;
+            }
+        }
+    }
+}
+
+// returns the number of solutions, at most limit, and the first solution found
+pub fn find_solutions(sudoku: &Sudoku, limit: usize) -> (usize, Option<Sudoku>) {
+    if limit == 0 || !sudoku.is_consistent() {
+        return (0, None);
+    }
+    let mut search = Search::new(sudoku, limit);
+    search.run();
+    (search.count, search.first)
+}
+
+pub fn find_solution_with_order(sudoku: &Sudoku, orders: Vec<Vec<Digit>>) -> Option<Sudoku> {
+    if !sudoku.is_consistent() {
+        return None;
+    }
+    let mut search = Search::new(sudoku, 1);
+    search.orders = orders;
+    search.run();
+    search.first
+}
+
+// calls visit with every solution, but no more than limit of them, and returns how many there
+// were; the candidates of the grids visit sees are not worked out
+pub fn for_each_solution(sudoku: &Sudoku, limit: usize, visit: &mut dyn FnMut(&Sudoku)) -> usize {
+    if limit == 0 || !sudoku.is_consistent() {
+        return 0;
+    }
+    let mut search = Search::new(sudoku, limit);
+    search.visit = Some(visit);
+    search.run();
+    search.count
+}
+
+// every solution, but no more than limit of them
+pub fn collect_solutions(sudoku: &Sudoku, limit: usize) -> Vec<Sudoku> {
+    let mut solutions = Vec::new();
+    for_each_solution(sudoku, limit, &mut |solution| {
+        let mut solution = solution.clone();
+        solution.reset_candidates();
+        solutions.push(solution);
+    });
+    solutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_solutions_of_a_puzzle() {
+        let sudoku = Sudoku::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let (count, solution) = find_solutions(&sudoku, 2);
+        assert_eq!(count, 1);
+        assert_eq!(
+            solution.unwrap().to_line(),
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179"
+        );
+    }
+    #[test]
+    fn test_find_solutions_stops_at_limit() {
+        let sudoku = Sudoku::new(9, 9, 3, 3);
+        assert_eq!(find_solutions(&sudoku, 3).0, 3);
+    }
+    #[test]
+    fn test_collect_solutions() {
+        let sudoku = Sudoku::new(4, 4, 2, 2);
+        let solutions = collect_solutions(&sudoku, 1000);
+        assert_eq!(solutions.len(), 288);
+        assert!(solutions.iter().all(|solution| solution.is_solved()));
+        assert_eq!(collect_solutions(&sudoku, 5).len(), 5);
+        let mut filled = 0;
+        let count = for_each_solution(&sudoku, 1000, &mut |solution| {
+            if solution.is_solved() {
+                filled += 1;
+            }
+        });
+        assert_eq!((count, filled), (288, 288));
+    }
+    #[test]
+    fn test_find_solutions_of_an_inconsistent_grid() {
+        let sudoku = Sudoku::from_line(
+            "55..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        assert_eq!(find_solutions(&sudoku, 2).0, 0);
+    }
+}
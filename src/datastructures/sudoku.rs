@@ -1,15 +1,32 @@
 // how to implement a set?
 // a set is a non organised collection of items on which certain operations can take place
 // use super::column::ColumnIterator;
-use super::digit::{Digit, DigitSet, DigitValue};
+use super::cage::Cage;
+use super::chess::{ChessConstraint, ChessPiece};
+use super::constraint::Constraint;
+use super::digit::{Digit, DigitHashSet, DigitSet, DigitValue};
+use super::edges::NonConsecutive;
 use super::griddimensions::{Cell, GridDimensions};
+use super::house::{House, HouseKind};
+use super::layout::Layout;
+use super::outside::OutsideClue;
+use super::solver;
 use std::collections::HashSet;
 // use super::row::RowIterator;
 // use super::section::{Section, SectionIterator};
 
+#[derive(Debug, Clone)]
 pub struct Sudoku {
     grid: Vec<Cell>,
     grid_dimensions: GridDimensions,
+    // every digit appears at most once in a house
+    houses: Vec<House>,
+    // the rules of a variant that are not houses
+    constraints: Vec<Box<dyn Constraint>>,
+    // for every cell the cells that can not hold the same digit, sorted
+    peers: Vec<Vec<usize>>,
+    // false for the cells of the canvas outside every grid of a layout
+    active: Vec<bool>,
 }
 
 pub struct SubSetIterator<'a> {
@@ -19,10 +36,7 @@ pub struct SubSetIterator<'a> {
 
 impl<'a> SubSetIterator<'a> {
     pub fn new(data: Vec<&'a Cell>) -> Self {
-        Self {
-            data: data,
-            current: 0,
-        }
+        Self { data, current: 0 }
     }
 }
 
@@ -34,29 +48,537 @@ impl<'a> Iterator for SubSetIterator<'a> {
             return None;
         }
         self.current += 1;
-        Some(&self.data[self.current - 1])
+        Some(self.data[self.current - 1])
     }
 }
 
 impl Sudoku {
     pub fn new(columns: usize, rows: usize, section_width: usize, section_height: usize) -> Self {
         let new_grid_dimensions = GridDimensions::new(columns, rows, section_width, section_height);
-        Sudoku {
+        let mut sudoku = Sudoku {
             grid: new_grid_dimensions.new_grid(),
+            houses: House::standard_houses(&new_grid_dimensions),
+            active: vec![true; new_grid_dimensions.get_data_size()],
             grid_dimensions: new_grid_dimensions,
+            constraints: Vec::new(),
+            peers: Vec::new(),
+        };
+        sudoku.update_peers();
+        sudoku.reset_candidates();
+        sudoku
+    }
+
+    // an empty puzzle of overlapping grids
+    pub fn from_layout(layout: &Layout) -> Self {
+        let dimensions = layout.get_grid_dimensions().clone();
+        let mut sudoku = Sudoku {
+            grid: dimensions.new_grid(),
+            houses: layout.houses(),
+            grid_dimensions: dimensions,
+            constraints: Vec::new(),
+            peers: Vec::new(),
+            active: layout.active_cells(),
+        };
+        sudoku.update_peers();
+        sudoku.reset_candidates();
+        sudoku
+    }
+
+    // reads the grids of a layout one after the other, every grid row by row; where grids overlap
+    // their givens have to agree
+    pub fn from_layout_line(layout: &Layout, line: &str) -> Option<Self> {
+        let characters: Vec<char> = line.trim().chars().collect();
+        let grid_size = *layout.get_grid_size();
+        if characters.len() != layout.get_origins().len() * grid_size * grid_size {
+            return None;
+        }
+        let mut sudoku = Sudoku::from_layout(layout);
+        let cells = (0..layout.get_origins().len()).flat_map(|grid| layout.grid_cells(grid));
+        for (index, character) in cells.zip(characters) {
+            let value = match character {
+                '.' | '0' => None,
+                _ => Some(Digit::from_char(character).filter(|d| d.to_usize() <= grid_size)?),
+            };
+            let cell = &mut sudoku.grid[index];
+            match (cell.get_value(), value) {
+                (Some(old), Some(new)) if *old != new => return None,
+                (_, Some(_)) => cell.set_value(value),
+                _ => {}
+            }
+        }
+        sudoku.reset_candidates();
+        Some(sudoku)
+    }
+
+    // the grids of a layout one after the other, as read by from_layout_line
+    pub fn to_layout_line(&self, layout: &Layout) -> String {
+        (0..layout.get_origins().len())
+            .flat_map(|grid| layout.grid_cells(grid))
+            .map(|index| self.grid[index].get_value().map_or('.', |d| d.to_char()))
+            .collect()
+    }
+
+    // reads a puzzle from a single line of n*n characters, digits are givens and '.' or '0' are
+    // empty cells; the section size is the square root of n
+    pub fn from_line(line: &str) -> Option<Self> {
+        let characters: Vec<char> = line.trim().chars().collect();
+        let size = (1..=9).find(|n| n * n == characters.len())?;
+        let section_size = (1..=3).find(|n| n * n == size)?;
+        let mut sudoku = Sudoku::new(size, size, section_size, section_size);
+        for (index, character) in characters.iter().enumerate() {
+            let value = match character {
+                '.' | '0' => None,
+                _ => Some(Digit::from_char(*character).filter(|d| d.to_usize() <= size)?),
+            };
+            sudoku.grid[index].set_value(value);
+        }
+        sudoku.reset_candidates();
+        Some(sudoku)
+    }
+
+    // a jigsaw puzzle from a line of givens and a line of the same length with a label for the
+    // section of every cell, any characters can be used as labels
+    pub fn from_jigsaw_lines(line: &str, regions: &str) -> Option<Self> {
+        let characters: Vec<char> = line.trim().chars().collect();
+        let size = (1..=9).find(|n| n * n == characters.len())?;
+        let width = (1..=size)
+            .find(|n| size % n == 0 && n * n >= size)
+            .unwrap_or(size);
+        let mut sudoku = Sudoku::new(size, size, width, size / width);
+        if !sudoku.set_regions(&parse_regions(regions)?) {
+            return None;
+        }
+        for (index, character) in characters.iter().enumerate() {
+            let value = match character {
+                '.' | '0' => None,
+                _ => Some(Digit::from_char(*character).filter(|d| d.to_usize() <= size)?),
+            };
+            sudoku.grid[index].set_value(value);
         }
+        sudoku.reset_candidates();
+        Some(sudoku)
+    }
+
+    // a killer puzzle from a line of givens, a line with a label for the cage of every cell ('.'
+    // for cells outside any cage) and the sums of the cages in the order their labels first appear
+    pub fn from_killer_lines(line: &str, cages: &str, sums: &[usize]) -> Option<Self> {
+        let mut sudoku = Sudoku::from_line(line)?;
+        let labels: Vec<char> = cages.trim().chars().collect();
+        if labels.len() != sudoku.grid.len() {
+            return None;
+        }
+        let mut order: Vec<char> = Vec::new();
+        for label in labels.iter().filter(|label| **label != '.') {
+            if !order.contains(label) {
+                order.push(*label);
+            }
+        }
+        if order.len() != sums.len() {
+            return None;
+        }
+        for (label, sum) in order.iter().zip(sums) {
+            let cells = (0..labels.len()).filter(|i| labels[*i] == *label).collect();
+            if !sudoku.add_cage(Cage::new(cells, *sum)) {
+                return None;
+            }
+        }
+        Some(sudoku)
+    }
+
+    pub fn to_line(&self) -> String {
+        self.grid
+            .iter()
+            .map(|cell| cell.get_value().map_or('.', |d| d.to_char()))
+            .collect()
     }
 
     pub fn distribute_set_to_make_correct_sudoku(&mut self, digitset: DigitSet) {
-        let rotation_counts = vec![0usize,3,6,1,4,7,2,5,8];
+        let rotation_counts = [0usize, 3, 6, 1, 4, 7, 2, 5, 8];
 
-        for index in 0..9 {
+        for (index, rotation_count) in rotation_counts.iter().enumerate() {
             let mut column = digitset.clone();
-            column.rotate_left(rotation_counts[index]);
+            column.rotate_left(*rotation_count);
             self.update_column(index, column);
         }
     }
-    
+
+    pub fn get_grid_dimensions(&self) -> &GridDimensions {
+        &self.grid_dimensions
+    }
+
+    // the cells in use, for a layout the ones outside every grid are left out
+    pub fn cells(&self) -> impl Iterator<Item = &Cell> {
+        self.grid
+            .iter()
+            .filter(move |cell| self.active[*cell.get_index()])
+    }
+
+    pub fn is_active(&self, index: usize) -> bool {
+        self.active.get(index).copied().unwrap_or(false)
+    }
+
+    // the digits that can be used in this grid, one for every cell of a section
+    pub fn get_digits(&self) -> Vec<Digit> {
+        Digit::all()
+            .into_iter()
+            .take(self.grid_dimensions.get_section_size())
+            .collect()
+    }
+
+    pub fn get_houses(&self) -> &Vec<House> {
+        &self.houses
+    }
+
+    pub fn get_constraints(&self) -> &Vec<Box<dyn Constraint>> {
+        &self.constraints
+    }
+
+    // the houses first, then the other rules
+    pub fn all_constraints(&self) -> impl Iterator<Item = &dyn Constraint> {
+        self.houses
+            .iter()
+            .map(|house| house as &dyn Constraint)
+            .chain(
+                self.constraints
+                    .iter()
+                    .map(|constraint| constraint.as_ref()),
+            )
+    }
+
+    pub fn add_house(&mut self, house: House) {
+        self.houses.push(house);
+        self.update_peers();
+        self.reset_candidates();
+    }
+
+    pub fn add_constraint(&mut self, constraint: Box<dyn Constraint>) {
+        self.constraints.push(constraint);
+        self.update_peers();
+        self.reset_candidates();
+    }
+
+    // the killer cages among the constraints
+    pub fn get_cages(&self) -> impl Iterator<Item = &Cage> {
+        self.constraints
+            .iter()
+            .filter_map(|constraint| constraint.downcast_ref::<Cage>())
+    }
+
+    // killer cage; false when it overlaps a cage that was already added, leaves the grid or its
+    // sum cannot be reached
+    pub fn add_cage(&mut self, cage: Cage) -> bool {
+        let cell_count = self.grid_dimensions.get_data_size();
+        let overlaps = self.get_cages().any(|other| {
+            other
+                .get_cells()
+                .iter()
+                .any(|i| cage.get_cells().contains(i))
+        });
+        if overlaps
+            || cage.get_cells().iter().any(|i| *i >= cell_count)
+            || !cage.is_possible(&self.get_digits())
+        {
+            return false;
+        }
+        self.add_constraint(Box::new(cage));
+        true
+    }
+
+    // replaces the sections with irregular ones, regions holds the section of every cell; false
+    // when they are not connected or not all as big as a row
+    pub fn set_regions(&mut self, regions: &[usize]) -> bool {
+        let sections = match House::regions(&self.grid_dimensions, regions) {
+            Some(sections) => sections,
+            None => return false,
+        };
+        self.houses
+            .retain(|house| house.get_kind() != &HouseKind::Section);
+        self.houses.extend(sections);
+        for (cell, region) in self.grid.iter_mut().zip(regions) {
+            cell.set_section(*region);
+        }
+        self.update_peers();
+        self.reset_candidates();
+        true
+    }
+
+    // the section of every cell, numbered as in set_regions
+    pub fn get_regions(&self) -> Vec<usize> {
+        self.grid.iter().map(|cell| *cell.get_section()).collect()
+    }
+
+    // x-sudoku: both main diagonals hold every digit once as well
+    pub fn add_diagonals(&mut self) {
+        self.houses
+            .extend(House::diagonals(&self.grid_dimensions));
+        self.update_peers();
+        self.reset_candidates();
+    }
+
+    // hyper sudoku: the four windows, and the regions they imply, hold every digit once
+    pub fn add_windows(&mut self) {
+        self.houses.extend(House::windows(&self.grid_dimensions));
+        self.update_peers();
+        self.reset_candidates();
+    }
+
+    // anti-knight or anti-king: cells a move of piece apart never hold the same digit
+    pub fn add_chess_constraint(&mut self, piece: ChessPiece) {
+        let constraint = ChessConstraint::new(piece, &self.grid_dimensions);
+        self.add_constraint(Box::new(constraint));
+    }
+
+    // clues outside the grid, one on every line as read by OutsideClue::parse; false, adding none
+    // of them, when a line can not be read
+    pub fn add_outside_clues(&mut self, text: &str) -> bool {
+        let clues: Option<Vec<OutsideClue>> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| OutsideClue::parse(&self.grid_dimensions, line))
+            .collect();
+        let clues = match clues {
+            Some(clues) => clues,
+            None => return false,
+        };
+        for clue in clues {
+            self.constraints.push(Box::new(clue));
+        }
+        self.update_peers();
+        self.reset_candidates();
+        true
+    }
+
+    // non-consecutive: digits in cells next to each other never differ by one
+    pub fn set_non_consecutive(&mut self, on: bool) {
+        self.constraints
+            .retain(|constraint| !constraint.is::<NonConsecutive>());
+        if on {
+            let rule = NonConsecutive::new(self);
+            self.constraints.push(Box::new(rule));
+        }
+        self.update_peers();
+        self.reset_candidates();
+    }
+
+    pub fn is_non_consecutive(&self) -> bool {
+        self.constraints
+            .iter()
+            .any(|constraint| constraint.is::<NonConsecutive>())
+    }
+
+    // disjoint groups: the cells in the same place of every section hold every digit once
+    pub fn set_disjoint_groups(&mut self, on: bool) {
+        self.houses
+            .retain(|house| house.get_kind() != &HouseKind::DisjointGroup);
+        if on {
+            self.houses
+                .extend(House::disjoint_groups(&self.grid_dimensions));
+        }
+        self.update_peers();
+        self.reset_candidates();
+    }
+
+    pub fn has_disjoint_groups(&self) -> bool {
+        self.houses
+            .iter()
+            .any(|house| house.get_kind() == &HouseKind::DisjointGroup)
+    }
+
+    // only rows, columns and sections, the rules the uniqueness techniques are built on
+    pub fn is_standard(&self) -> bool {
+        self.constraints.is_empty()
+            && self.houses.iter().all(|house| {
+                matches!(
+                    house.get_kind(),
+                    HouseKind::Row | HouseKind::Column | HouseKind::Section
+                )
+            })
+            && self.houses.len() == House::standard_houses(&self.grid_dimensions).len()
+            && self.active.iter().all(|active| *active)
+    }
+
+    // standard rules on a square grid whose sections are the regular ones of its dimensions, the
+    // grids the transformations below work on
+    pub fn has_regular_sections(&self) -> bool {
+        self.is_standard()
+            && self.grid_dimensions.get_row_count() == self.grid_dimensions.get_column_count()
+            && self.grid.iter().all(|cell| {
+                *cell.get_section()
+                    == self
+                        .grid_dimensions
+                        .get_section_for_index(*cell.get_index())
+            })
+    }
+
+    // moves every cell to the row and column place gives it in a grid of dimensions, values and
+    // candidates go along; false, leaving the grid as it is, unless it has regular sections
+    fn rearrange(
+        &mut self,
+        dimensions: GridDimensions,
+        place: impl Fn(usize, usize) -> (usize, usize),
+    ) -> bool {
+        if !self.has_regular_sections() {
+            return false;
+        }
+        let mut grid = dimensions.new_grid();
+        for cell in &self.grid {
+            let (row, column) = place(*cell.get_row(), *cell.get_column());
+            let target = &mut grid[row * dimensions.get_column_count() + column];
+            target.set_value(*cell.get_value());
+            target.set_candidates(cell.get_candidates().clone());
+        }
+        self.houses = House::standard_houses(&dimensions);
+        self.grid = grid;
+        self.grid_dimensions = dimensions;
+        self.update_peers();
+        true
+    }
+
+    // the same dimensions with rows and columns swapped
+    fn turned_dimensions(&self) -> GridDimensions {
+        let dimensions = &self.grid_dimensions;
+        GridDimensions::new(
+            *dimensions.get_row_count(),
+            *dimensions.get_column_count(),
+            *dimensions.get_section_height(),
+            *dimensions.get_section_width(),
+        )
+    }
+
+    // digits[i] takes the place of digit i + 1, digits has to hold every digit of the grid once
+    pub fn relabel(&mut self, digits: &[Digit]) -> bool {
+        let mut sorted: Vec<usize> = digits.iter().map(|d| d.to_usize()).collect();
+        sorted.sort_unstable();
+        if sorted != (1..=self.get_digits().len()).collect::<Vec<usize>>()
+            || !self.has_regular_sections()
+        {
+            return false;
+        }
+        let new_digit = |digit: &Digit| digits[digit.to_usize() - 1];
+        for cell in self.grid.iter_mut() {
+            let value = cell.get_value().map(|d| new_digit(&d));
+            let candidates = cell.get_candidates().iter().map(new_digit).collect();
+            cell.set_value(value);
+            cell.set_candidates(candidates);
+        }
+        true
+    }
+
+    // two rows of the same band
+    pub fn swap_rows(&mut self, first: usize, second: usize) -> bool {
+        let height = *self.grid_dimensions.get_section_height();
+        let rows = *self.grid_dimensions.get_row_count();
+        if first >= rows || second >= rows || first / height != second / height {
+            return false;
+        }
+        let swap = |line: usize| swapped(line, first, second);
+        self.rearrange(self.grid_dimensions.clone(), |row, column| {
+            (swap(row), column)
+        })
+    }
+
+    // two columns of the same stack
+    pub fn swap_columns(&mut self, first: usize, second: usize) -> bool {
+        let width = *self.grid_dimensions.get_section_width();
+        let columns = *self.grid_dimensions.get_column_count();
+        if first >= columns || second >= columns || first / width != second / width {
+            return false;
+        }
+        let swap = |line: usize| swapped(line, first, second);
+        self.rearrange(self.grid_dimensions.clone(), |row, column| {
+            (row, swap(column))
+        })
+    }
+
+    // two bands, the rows of a section each, with their rows in the same order
+    pub fn swap_bands(&mut self, first: usize, second: usize) -> bool {
+        let height = *self.grid_dimensions.get_section_height();
+        let bands = self.grid_dimensions.get_sections_in_column();
+        if first >= bands || second >= bands {
+            return false;
+        }
+        let swap = |line: usize| swapped(line / height, first, second) * height + line % height;
+        self.rearrange(self.grid_dimensions.clone(), |row, column| {
+            (swap(row), column)
+        })
+    }
+
+    // two stacks, the columns of a section each, with their columns in the same order
+    pub fn swap_stacks(&mut self, first: usize, second: usize) -> bool {
+        let width = *self.grid_dimensions.get_section_width();
+        let stacks = self.grid_dimensions.get_sections_in_row();
+        if first >= stacks || second >= stacks {
+            return false;
+        }
+        let swap = |line: usize| swapped(line / width, first, second) * width + line % width;
+        self.rearrange(self.grid_dimensions.clone(), |row, column| {
+            (row, swap(column))
+        })
+    }
+
+    // rows become columns; sections that are wider than high become higher than wide
+    pub fn transpose(&mut self) -> bool {
+        self.rearrange(self.turned_dimensions(), |row, column| (column, row))
+    }
+
+    // a quarter turn clockwise
+    pub fn rotate(&mut self) -> bool {
+        let last = self.grid_dimensions.get_row_count() - 1;
+        self.rearrange(self.turned_dimensions(), |row, column| (column, last - row))
+    }
+
+    // mirrored left to right
+    pub fn reflect_horizontally(&mut self) -> bool {
+        let last = self.grid_dimensions.get_column_count() - 1;
+        self.rearrange(self.grid_dimensions.clone(), |row, column| {
+            (row, last - column)
+        })
+    }
+
+    // mirrored top to bottom
+    pub fn reflect_vertically(&mut self) -> bool {
+        let last = self.grid_dimensions.get_row_count() - 1;
+        self.rearrange(self.grid_dimensions.clone(), |row, column| {
+            (last - row, column)
+        })
+    }
+
+    fn update_peers(&mut self) {
+        let cell_count = self.grid_dimensions.get_data_size();
+        let mut peers: Vec<Vec<usize>> = vec![Vec::new(); cell_count];
+        for house in &self.houses {
+            for index in house.get_cells() {
+                peers[*index].extend(house.get_cells().iter().filter(|peer| *peer != index));
+            }
+        }
+        for constraint in &self.constraints {
+            let cells = constraint.get_cells();
+            for first in &cells {
+                peers[*first].extend(
+                    cells
+                        .iter()
+                        .filter(|second| constraint.sees(*first, **second)),
+                );
+            }
+        }
+        for list in peers.iter_mut() {
+            list.sort_unstable();
+            list.dedup();
+        }
+        self.peers = peers;
+    }
+
+    // all cells that can not hold the same digit as the cell at index
+    pub fn get_peers(&self, index: usize) -> Vec<usize> {
+        self.peers.get(index).cloned().unwrap_or_default()
+    }
+
+    pub fn sees(&self, first: usize, second: usize) -> bool {
+        self.peers
+            .get(first)
+            .is_some_and(|list| list.binary_search(&second).is_ok())
+    }
+
     fn subset<'a>(&'a self, indices: Vec<usize>) -> impl Iterator<Item = &'a Cell> + 'a {
         let subset = indices.iter().map(|x| self.get_cell(*x).unwrap()).collect();
         SubSetIterator::new(subset)
@@ -73,29 +595,49 @@ impl Sudoku {
     }
 
     pub fn section<'a>(&'a self, section: usize) -> impl Iterator<Item = &'a Cell> + 'a {
-        let indices = self.grid_dimensions.get_indices_for_section(section);
+        let indices = self
+            .houses
+            .iter()
+            .find(|house| house.get_kind() == &HouseKind::Section && house.get_number() == &section)
+            .map_or(Vec::new(), |house| house.get_cells().clone());
         self.subset(indices)
     }
 
+    // the digits the rules allow in the cell, None when one of its houses already breaks them
     pub fn available_digits_for_cell(&self, cell: &Cell) -> Option<HashSet<Digit>> {
-        let columnset = self.unused_digits_in_column(*cell.get_column());
-        let rowset = self.unused_digits_in_row(*cell.get_row());
-        let sectionset = self.unused_digits_in_section(*cell.get_section());
-
-        if columnset.is_none() || rowset.is_none() || sectionset.is_none() {
-            return None;
+        let index = *cell.get_index();
+        let mut available: HashSet<Digit> = self.get_digits().into_iter().collect();
+        for house in self.houses.iter().filter(|house| house.contains(index)) {
+            for digit in house.used_digits(self)? {
+                available.remove(&digit);
+            }
         }
-
-        let mut intersection: HashSet<Digit> = columnset
-            .unwrap()
-            .intersection(&rowset.unwrap())
-            .map(|x| *x)
+        let rules: Vec<&Box<dyn Constraint>> = self
+            .constraints
+            .iter()
+            .filter(|constraint| constraint.get_cells().contains(&index))
             .collect();
-        intersection = intersection
-            .intersection(&sectionset.unwrap())
-            .map(|x| *x)
+        if rules.is_empty() {
+            return Some(available);
+        }
+        // the rules trim the cell's set, the other empty cells may still hold any digit
+        let digits: DigitHashSet = self.get_digits().into_iter().collect();
+        let mut candidates: Vec<DigitHashSet> = self
+            .grid
+            .iter()
+            .map(|cell| {
+                if cell.is_empty() {
+                    digits.clone()
+                } else {
+                    DigitHashSet::new()
+                }
+            })
             .collect();
-        Some(intersection)
+        candidates[index] = available;
+        for rule in rules {
+            rule.restrict_candidates(self, &mut candidates);
+        }
+        Some(candidates.swap_remove(index))
     }
 
     // pub fn sort_cells_by_freedom<'a>(&'a self) -> impl Iterator<Item = &'a Cell> + 'a {
@@ -140,18 +682,13 @@ impl Sudoku {
     where
         I: Iterator<Item = &'a Cell>,
     {
-        use super::digit::Digit::*;
-
-        let mut result: HashSet<_> = [One, Two, Three, Four, Five, Six, Seven, Eight, Nine]
-            .iter()
-            .cloned()
-            .collect();
+        let mut result: HashSet<_> = self.get_digits().into_iter().collect();
         let mut found_more_than_one_digit_in_set: bool = false;
         vals.filter(|x| x.get_value().is_some())
             .collect::<Vec<&Cell>>()
             .iter()
             .for_each(|x| {
-                found_more_than_one_digit_in_set |= !result.remove(&x.get_value().as_ref().unwrap())
+                found_more_than_one_digit_in_set |= !result.remove(x.get_value().as_ref().unwrap())
             });
         if !found_more_than_one_digit_in_set {
             Some(result)
@@ -176,7 +713,17 @@ impl Sudoku {
         self.grid_dimensions.is_valid()
     }
 
-    fn get_cell(&self, index: usize) -> Option<&Cell> {
+    // none of the rules is broken by the digits placed so far
+    pub fn is_consistent(&self) -> bool {
+        self.all_constraints()
+            .all(|constraint| constraint.is_valid(self))
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells().all(|cell| !cell.is_empty()) && self.is_consistent()
+    }
+
+    pub fn get_cell(&self, index: usize) -> Option<&Cell> {
         if index < self.grid_dimensions.get_data_size() {
             Some(&self.grid[index])
         } else {
@@ -202,25 +749,157 @@ impl Sudoku {
     //     *cell.get_value()
     // }
 
-    pub fn solve(&mut self) -> bool {
-        let mut row_sets: Vec<Option<HashSet<Digit>>> = Vec::new();
-        let mut column_sets: Vec<Option<HashSet<Digit>>> = Vec::new();
-        let mut section_sets: Vec<Option<HashSet<Digit>>> = Vec::new();
-
-        (0..*self.grid_dimensions.get_column_count())
-            .collect::<Vec<usize>>()
-            .iter()
-            .for_each(|x| row_sets.push(self.unused_digits_in_row(*x)));
-        (0..*self.grid_dimensions.get_row_count())
-            .collect::<Vec<usize>>()
+    // recalculates the candidates of every empty cell from the digits placed so far
+    pub fn reset_candidates(&mut self) {
+        let digits: DigitHashSet = self.get_digits().into_iter().collect();
+        let mut candidates: Vec<DigitHashSet> = self
+            .grid
             .iter()
-            .for_each(|x| column_sets.push(self.unused_digits_in_column(*x)));
-        (0..self.grid_dimensions.get_section_count())
-            .collect::<Vec<usize>>()
+            .map(|cell| {
+                if cell.is_empty() && self.active[*cell.get_index()] {
+                    digits.clone()
+                } else {
+                    DigitHashSet::new()
+                }
+            })
+            .collect();
+        for house in &self.houses {
+            house.restrict_candidates(self, &mut candidates);
+        }
+        self.propagate(&mut candidates, (0..self.constraints.len()).collect());
+        for (cell, candidates) in self.grid.iter_mut().zip(candidates) {
+            cell.set_candidates(candidates);
+        }
+    }
+
+    // applies the rules, then again the rules on every cell whose candidates changed until
+    // nothing changes any more, which carries chains of rules such as inequalities along
+    fn propagate(&self, candidates: &mut [DigitHashSet], mut rules: Vec<usize>) {
+        while !rules.is_empty() {
+            let before = candidates.to_vec();
+            for rule in &rules {
+                self.constraints[*rule].restrict_candidates(self, candidates);
+            }
+            let changed: Vec<usize> = (0..candidates.len())
+                .filter(|i| candidates[*i] != before[*i])
+                .collect();
+            rules = (0..self.constraints.len())
+                .filter(|rule| {
+                    let constraint = &self.constraints[*rule];
+                    !constraint.is_pairwise()
+                        && constraint.get_cells().iter().any(|i| changed.contains(i))
+                })
+                .collect();
+        }
+    }
+
+    // places a digit and removes it from the candidates of all peers
+    pub fn set_digit(&mut self, index: usize, digit: Digit) {
+        if index >= self.grid_dimensions.get_data_size() {
+            return;
+        }
+        self.grid[index].set_value(Some(digit));
+        self.grid[index].set_candidates(DigitHashSet::new());
+        for peer in self.get_peers(index) {
+            self.grid[peer].remove_candidate(digit);
+        }
+        // the other rules on the cell may rule out more candidates now
+        let rules: Vec<usize> = (0..self.constraints.len())
+            .filter(|rule| {
+                let constraint = &self.constraints[*rule];
+                !constraint.is_pairwise() && constraint.get_cells().contains(&index)
+            })
+            .collect();
+        if rules.is_empty() {
+            return;
+        }
+        let mut candidates: Vec<DigitHashSet> = self
+            .grid
             .iter()
-            .for_each(|x| section_sets.push(self.unused_digits_in_column(*x)));
-        // find cell with lowest number of posibilities
-        false
+            .map(|cell| cell.get_candidates().clone())
+            .collect();
+        self.propagate(&mut candidates, rules);
+        for (cell, candidates) in self.grid.iter_mut().zip(candidates) {
+            cell.set_candidates(candidates);
+        }
+    }
+
+    pub fn remove_candidate(&mut self, index: usize, digit: Digit) -> bool {
+        match self.get_mut_cell(index) {
+            Some(cell) => cell.remove_candidate(digit),
+            None => false,
+        }
+    }
+
+    // fills the grid with the first solution found, returns false if there is none
+    pub fn solve(&mut self) -> bool {
+        match self.get_solution() {
+            Some(solution) => {
+                *self = solution;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_solution(&self) -> Option<Sudoku> {
+        solver::find_solutions(self, 1).1
+    }
+
+    // counts solutions, but stops searching once limit solutions are found
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        solver::find_solutions(self, limit).0
+    }
+
+    // all solutions, or the first limit of them
+    pub fn get_solutions(&self, limit: usize) -> Vec<Sudoku> {
+        solver::collect_solutions(self, limit)
+    }
+
+    // hands every solution, or the first limit of them, to visit without keeping them; returns
+    // how many there were
+    pub fn for_each_solution(&self, limit: usize, mut visit: impl FnMut(&Sudoku)) -> usize {
+        solver::for_each_solution(self, limit, &mut visit)
+    }
+
+    // the first solution found trying the digits of every cell in the given order, orders holds
+    // one list for every cell
+    pub fn get_solution_with_order(&self, orders: Vec<Vec<Digit>>) -> Option<Sudoku> {
+        solver::find_solution_with_order(self, orders)
+    }
+
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    // the givens that can each be taken away on their own without losing the unique solution,
+    // None when there is no unique solution to lose
+    pub fn redundant_givens(&self) -> Option<Vec<usize>> {
+        if !self.has_unique_solution() {
+            return None;
+        }
+        let mut puzzle = self.clone();
+        let givens: Vec<usize> = self
+            .cells()
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| *cell.get_index())
+            .collect();
+        let mut redundant = Vec::new();
+        for index in givens {
+            let digit = *puzzle.grid[index].get_value();
+            puzzle.grid[index].set_value(None);
+            if puzzle.has_unique_solution() {
+                redundant.push(index);
+            }
+            puzzle.grid[index].set_value(digit);
+        }
+        Some(redundant)
+    }
+
+    // a unique solution that every given is needed for
+    pub fn is_minimal(&self) -> bool {
+        self.redundant_givens()
+            .is_some_and(|redundant| redundant.is_empty())
     }
 
     fn _update_column(&mut self, column: usize, values: Vec<DigitValue>) {
@@ -239,6 +918,38 @@ impl Sudoku {
     }
 }
 
+// line with first and second swapped
+fn swapped(line: usize, first: usize, second: usize) -> usize {
+    if line == first {
+        second
+    } else if line == second {
+        first
+    } else {
+        line
+    }
+}
+
+// labels the sections in the order they first appear, the first one becomes section 0
+fn parse_regions(line: &str) -> Option<Vec<usize>> {
+    let mut labels: Vec<char> = Vec::new();
+    let mut regions = Vec::new();
+    for character in line.trim().chars() {
+        let region = match labels.iter().position(|label| *label == character) {
+            Some(region) => region,
+            None => {
+                labels.push(character);
+                labels.len() - 1
+            }
+        };
+        regions.push(region);
+    }
+    if regions.is_empty() {
+        None
+    } else {
+        Some(regions)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,7 +957,7 @@ mod tests {
     #[test]
     fn test_to_make_an_empty_sudoku() {
         let mysudoku = Sudoku::new(9, 9, 3, 3);
-        assert_eq!(mysudoku.is_valid(), true);
+        assert!(mysudoku.is_valid());
     }
     #[test]
     fn test_get_a_row() {
@@ -287,13 +998,13 @@ mod tests {
     #[test]
     fn test_unused_digits_in_row() {
         let mysudoku = Sudoku::new(9, 9, 3, 3);
-        assert_eq!(mysudoku.unused_digits_in_row(3).unwrap().iter().count(), 9);
+        assert_eq!(mysudoku.unused_digits_in_row(3).unwrap().len(), 9);
     }
     #[test]
     fn test_unused_digits_in_column() {
         let mysudoku = Sudoku::new(9, 9, 3, 3);
         assert_eq!(
-            mysudoku.unused_digits_in_column(3).unwrap().iter().count(),
+            mysudoku.unused_digits_in_column(3).unwrap().len(),
             9
         );
     }
@@ -301,55 +1012,388 @@ mod tests {
     fn test_unused_digits_in_section() {
         let mysudoku = Sudoku::new(9, 9, 3, 3);
         assert_eq!(
-            mysudoku.unused_digits_in_section(5).unwrap().iter().count(),
+            mysudoku.unused_digits_in_section(5).unwrap().len(),
             9
         );
     }
     #[test]
     fn test_used_digits_in_row() {
         let mysudoku = Sudoku::new(9, 9, 3, 3);
-        assert_eq!(mysudoku.used_digits_in_row(3).unwrap().iter().count(), 0);
+        assert_eq!(mysudoku.used_digits_in_row(3).unwrap().len(), 0);
     }
     #[test]
     fn test_used_digits_in_column() {
         let mysudoku = Sudoku::new(9, 9, 3, 3);
-        assert_eq!(mysudoku.used_digits_in_row(3).unwrap().iter().count(), 0);
+        assert_eq!(mysudoku.used_digits_in_row(3).unwrap().len(), 0);
     }
     #[test]
     fn test_used_digits_in_section() {
         let mysudoku = Sudoku::new(9, 9, 3, 3);
         assert_eq!(
-            mysudoku.used_digits_in_section(3).unwrap().iter().count(),
+            mysudoku.used_digits_in_section(3).unwrap().len(),
             0
         );
     }
     #[test]
     fn test_solve_sudoku() {
         let mut mysudoku = Sudoku::new(9, 9, 3, 3);
-        assert_eq!(mysudoku.solve(), true);
+        assert!(mysudoku.solve());
     }
     #[test]
     fn test_update_column() {
         let mut mysudoku = Sudoku::new(9, 9, 3, 3);
         let row = DigitSet::new_full();
         mysudoku.update_column(0, row);
-        assert_eq!(mysudoku.used_digits_in_column(0).unwrap().iter().count(), 9);
+        assert_eq!(mysudoku.used_digits_in_column(0).unwrap().len(), 9);
     }
     #[test]
     fn test_used_digits_for_correct_sudoku() {
         let mut mysudoku = Sudoku::new(9, 9, 3, 3);
         mysudoku.distribute_set_to_make_correct_sudoku(DigitSet::new_full());
         for i in 0usize..9usize {
-            assert_eq!(mysudoku.used_digits_in_column(i).unwrap().iter().count(), 9, "column {}", i);
-            assert_eq!(mysudoku.used_digits_in_row(i).unwrap().iter().count(), 9, "row {}", i);
-            assert_eq!(mysudoku.used_digits_in_section(i).unwrap().iter().count(), 9, "section {}", i)
+            assert_eq!(mysudoku.used_digits_in_column(i).unwrap().len(), 9, "column {}", i);
+            assert_eq!(mysudoku.used_digits_in_row(i).unwrap().len(), 9, "row {}", i);
+            assert_eq!(mysudoku.used_digits_in_section(i).unwrap().len(), 9, "section {}", i)
         }
     }
     #[test]
+    fn test_read_and_write_a_line() {
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let mysudoku = Sudoku::from_line(line).unwrap();
+        assert_eq!(mysudoku.to_line(), line);
+        assert_eq!(mysudoku.get_cell(1).unwrap().get_value(), &Some(Digit::Three));
+        assert!(Sudoku::from_line("53..7").is_none());
+        assert!(Sudoku::from_line("1234341221434321").is_some());
+        assert!(Sudoku::from_line("1235341221434321").is_none());
+    }
+    #[test]
+    fn test_candidates_after_reading() {
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let mysudoku = Sudoku::from_line(line).unwrap();
+        let candidates = mysudoku.get_cell(2).unwrap().get_candidates();
+        let expected: DigitHashSet = [Digit::One, Digit::Two, Digit::Four].iter().copied().collect();
+        assert_eq!(candidates, &expected);
+        assert!(mysudoku.get_cell(0).unwrap().get_candidates().is_empty());
+    }
+    #[test]
+    fn test_set_digit_removes_candidates_from_peers() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        mysudoku.set_digit(0, Digit::Five);
+        assert!(!mysudoku.get_cell(8).unwrap().has_candidate(Digit::Five));
+        assert!(!mysudoku.get_cell(72).unwrap().has_candidate(Digit::Five));
+        assert!(!mysudoku.get_cell(20).unwrap().has_candidate(Digit::Five));
+        assert!(mysudoku.get_cell(30).unwrap().has_candidate(Digit::Five));
+    }
+    #[test]
+    fn test_peers() {
+        let mysudoku = Sudoku::new(9, 9, 3, 3);
+        assert_eq!(mysudoku.get_peers(40).len(), 20);
+        assert!(mysudoku.sees(0, 20));
+        assert!(!mysudoku.sees(0, 30));
+    }
+    #[test]
+    fn test_solve_and_uniqueness() {
+        let line = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let mut mysudoku = Sudoku::from_line(line).unwrap();
+        assert!(mysudoku.has_unique_solution());
+        assert!(mysudoku.solve());
+        assert!(mysudoku.is_solved());
+        assert!(!Sudoku::new(9, 9, 3, 3).has_unique_solution());
+    }
+    #[test]
     fn test_sort_cells_by_freedom() {
         let mut mysudoku = Sudoku::new(9, 9, 3, 3);
         let row = DigitSet::new_full();
         mysudoku.update_column(0, row);
-        assert_eq!(mysudoku.used_digits_in_column(0).unwrap().iter().count(), 9);
+        assert_eq!(mysudoku.used_digits_in_column(0).unwrap().len(), 9);
+    }
+    #[derive(Debug, Clone)]
+    struct EvenCell {
+        index: usize,
+    }
+
+    impl Constraint for EvenCell {
+        fn get_name(&self) -> String {
+            String::from("even cell")
+        }
+        fn get_cells(&self) -> Vec<usize> {
+            vec![self.index]
+        }
+        fn is_valid(&self, sudoku: &Sudoku) -> bool {
+            sudoku
+                .get_cell(self.index)
+                .unwrap()
+                .get_value()
+                .is_none_or(|d| d.to_usize() % 2 == 0)
+        }
+        fn box_clone(&self) -> Box<dyn Constraint> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_extra_house() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        let diagonal: Vec<usize> = (0..9).map(|i| i * 10).collect();
+        mysudoku.add_house(House::new(HouseKind::Diagonal, 0, diagonal));
+        assert!(mysudoku.sees(0, 80));
+        mysudoku.set_digit(0, Digit::One);
+        assert!(!mysudoku.get_cell(80).unwrap().has_candidate(Digit::One));
+        let solution = mysudoku.get_solution().unwrap();
+        assert!(solution.is_solved());
+    }
+    #[test]
+    fn test_constraints_limit_candidates_and_solutions() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        mysudoku.add_constraint(Box::new(EvenCell { index: 0 }));
+        assert_eq!(mysudoku.get_cell(0).unwrap().get_candidates().len(), 4);
+        let solution = mysudoku.get_solution().unwrap();
+        assert_eq!(solution.get_cell(0).unwrap().get_value(), &Some(Digit::Two));
+        mysudoku.get_mut_cell(0).unwrap().set_value(Some(Digit::Three));
+        assert!(!mysudoku.is_consistent());
+    }
+    #[test]
+    fn test_rules_limit_available_digits() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        mysudoku.add_constraint(Box::new(EvenCell { index: 0 }));
+        mysudoku.set_non_consecutive(true);
+        mysudoku.get_mut_cell(1).unwrap().set_value(Some(Digit::Three));
+        let cell = mysudoku.get_cell(0).unwrap().clone();
+        let available = mysudoku.available_digits_for_cell(&cell).unwrap();
+        let expected: HashSet<Digit> = vec![Digit::Six, Digit::Eight].into_iter().collect();
+        assert_eq!(available, expected);
+    }
+    #[test]
+    fn test_diagonal_sudoku() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        assert!(mysudoku.is_standard());
+        mysudoku.add_diagonals();
+        assert!(!mysudoku.is_standard());
+        assert_eq!(mysudoku.get_houses().len(), 29);
+        assert!(mysudoku.sees(8, 72));
+        mysudoku.get_mut_cell(0).unwrap().set_value(Some(Digit::One));
+        mysudoku.get_mut_cell(80).unwrap().set_value(Some(Digit::One));
+        assert!(!mysudoku.is_consistent());
+        let cell = mysudoku.get_cell(40).unwrap().clone();
+        assert!(mysudoku.available_digits_for_cell(&cell).is_none());
+    }
+    const JIGSAW_REGIONS: &str = "111222333111222333114222333144555666444555666444555666777888999777888999777888999";
+
+    #[test]
+    fn test_jigsaw_sudoku() {
+        let empty = ".".repeat(81);
+        let mysudoku = Sudoku::from_jigsaw_lines(&empty, JIGSAW_REGIONS).unwrap();
+        assert_eq!(mysudoku.get_regions()[20], 3);
+        assert_eq!(mysudoku.get_regions()[27], 0);
+        assert!(mysudoku.sees(1, 27));
+        assert!(!mysudoku.sees(0, 20));
+        assert_eq!(mysudoku.section(0).count(), 9);
+        let solution = mysudoku.get_solution().unwrap();
+        assert!(solution.is_solved());
+        let mut section: Vec<usize> = solution
+            .section(3)
+            .map(|cell| cell.get_value().unwrap().to_usize())
+            .collect();
+        section.sort_unstable();
+        assert_eq!(section, (1..=9).collect::<Vec<usize>>());
+        let split = JIGSAW_REGIONS.replacen("114", "411", 1);
+        assert!(Sudoku::from_jigsaw_lines(&empty, &split).is_none());
+    }
+    #[test]
+    fn test_windoku() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        mysudoku.add_windows();
+        assert!(!mysudoku.is_standard());
+        assert_eq!(mysudoku.get_houses().len(), 27 + 9);
+        // r2c2 and r4c4 share the first window, r1c1 and r5c5 the implied window of the outer
+        // and middle lines
+        assert!(mysudoku.sees(10, 30));
+        assert!(mysudoku.sees(0, 40));
+        assert!(!mysudoku.sees(0, 30));
+        let solution = mysudoku.get_solution().unwrap();
+        assert!(solution.is_solved());
+        assert!(solution.is_consistent());
+        assert!(House::windows(&GridDimensions::new(6, 6, 3, 2)).is_empty());
+    }
+    #[test]
+    fn test_samurai_sudoku() {
+        let layout = Layout::samurai();
+        let mysudoku = Sudoku::from_layout(&layout);
+        assert!(!mysudoku.is_standard());
+        assert!(!mysudoku.is_active(9));
+        assert_eq!(mysudoku.cells().count(), 369);
+        // r1c1 and r21c21 are in different grids, r7c7 is shared by the first and the middle one
+        assert!(!mysudoku.sees(0, 440));
+        assert!(mysudoku.sees(6 * 21 + 6, 6 * 21 + 14));
+        let solution = mysudoku.get_solution().unwrap();
+        assert!(solution.is_solved());
+        assert!(solution.get_cell(9).unwrap().is_empty());
+        let line = solution.to_layout_line(&layout);
+        assert_eq!(line.len(), 5 * 81);
+        let read = Sudoku::from_layout_line(&layout, &line).unwrap();
+        assert_eq!(read.to_line(), solution.to_line());
+        // the first grid puts a different digit in the section it shares with the middle grid
+        let mut conflicting: Vec<char> = line.chars().collect();
+        conflicting[80] = if conflicting[80] == '1' { '2' } else { '1' };
+        let conflicting: String = conflicting.into_iter().collect();
+        assert!(Sudoku::from_layout_line(&layout, &conflicting).is_none());
+    }
+    #[test]
+    fn test_twin_sudoku_uniqueness() {
+        let layout = Layout::twin();
+        let solution = Sudoku::from_layout(&layout).get_solution().unwrap();
+        let line = solution.to_layout_line(&layout);
+        let mut puzzle = Sudoku::from_layout_line(&layout, &line).unwrap();
+        assert!(puzzle.has_unique_solution());
+        // the first cell of the shared section can be worked out from the rest of either grid
+        let shared = 6 * 15 + 6;
+        puzzle.get_mut_cell(shared).unwrap().set_value(None);
+        puzzle.get_mut_cell(0).unwrap().set_value(None);
+        assert!(puzzle.has_unique_solution());
+        assert_eq!(puzzle.get_solution().unwrap().to_layout_line(&layout), line);
+    }
+    #[test]
+    fn test_outside_clues() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        assert!(!mysudoku.add_outside_clues("sandwich top 1 35\nskyscraper up 1 3"));
+        assert!(mysudoku.get_constraints().is_empty());
+        assert!(mysudoku.add_outside_clues("sandwich top 1 35\n\nskyscraper left 1 1\n"));
+        assert_eq!(mysudoku.get_constraints().len(), 2);
+        // the sandwich puts the crust at both ends of the column, the skyscraper a nine in r1c1
+        assert_eq!(mysudoku.get_cell(0).unwrap().get_candidates().len(), 1);
+        let solution = mysudoku.get_solution().unwrap();
+        assert_eq!(
+            solution.get_cell(72).unwrap().get_value(),
+            &Some(Digit::One)
+        );
+    }
+    #[test]
+    fn test_non_consecutive() {
+        let mut mysudoku = Sudoku::new(6, 6, 3, 2);
+        mysudoku.set_non_consecutive(true);
+        assert!(mysudoku.is_non_consecutive());
+        assert!(!mysudoku.is_standard());
+        mysudoku.set_digit(8, Digit::Five);
+        for neighbour in [2, 7, 9, 14] {
+            let candidates = mysudoku.get_cell(neighbour).unwrap().get_candidates();
+            assert!(!candidates.contains(&Digit::Four) && !candidates.contains(&Digit::Six));
+        }
+        let solution = mysudoku.get_solution().unwrap();
+        assert!(solution.is_solved());
+        let first = solution
+            .get_cell(0)
+            .unwrap()
+            .get_value()
+            .unwrap()
+            .to_usize();
+        let second = solution
+            .get_cell(1)
+            .unwrap()
+            .get_value()
+            .unwrap()
+            .to_usize();
+        assert_ne!(first.abs_diff(second), 1);
+        // switching the rule off leaves the other rules alone
+        mysudoku.add_constraint(Box::new(EvenCell { index: 0 }));
+        mysudoku.set_non_consecutive(false);
+        assert!(!mysudoku.is_non_consecutive());
+        assert_eq!(mysudoku.get_constraints().len(), 1);
+    }
+    #[test]
+    fn test_disjoint_groups() {
+        let mut mysudoku = Sudoku::new(9, 9, 3, 3);
+        mysudoku.set_disjoint_groups(true);
+        mysudoku.set_disjoint_groups(true);
+        assert!(mysudoku.has_disjoint_groups());
+        assert_eq!(mysudoku.get_houses().len(), 27 + 9);
+        // the top left cells of sections 1 and 5
+        assert!(mysudoku.sees(0, 30));
+        assert!(!mysudoku.sees(0, 31));
+        let solution = mysudoku.get_solution().unwrap();
+        assert!(solution.is_consistent());
+        mysudoku.set_disjoint_groups(false);
+        assert!(mysudoku.is_standard());
+        assert!(House::disjoint_groups(&GridDimensions::new(6, 6, 3, 2)).len() == 6);
+    }
+    #[test]
+    fn test_transformations() {
+        let line =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let original = Sudoku::from_line(line).unwrap();
+        let mut sudoku = original.clone();
+        assert!(sudoku.swap_rows(0, 2));
+        assert!(!sudoku.swap_rows(2, 3));
+        assert!(sudoku.swap_columns(4, 5));
+        assert!(!sudoku.swap_columns(0, 9));
+        assert!(sudoku.swap_bands(0, 2));
+        assert!(sudoku.swap_stacks(1, 2));
+        assert!(sudoku.transpose());
+        assert!(sudoku.rotate());
+        assert!(sudoku.reflect_horizontally());
+        assert!(sudoku.reflect_vertically());
+        let digits: Vec<Digit> = Digit::all().into_iter().rev().collect();
+        assert!(sudoku.relabel(&digits));
+        assert!(!sudoku.relabel(&digits[1..]));
+        assert!(sudoku.is_consistent());
+        assert!(sudoku.has_unique_solution());
+        assert_ne!(sudoku.to_line(), line);
+        assert!(crate::symmetry::are_equivalent(&original, &sudoku));
+        let mut turned = original.clone();
+        for _ in 0..4 {
+            assert!(turned.rotate());
+        }
+        assert_eq!(turned.to_line(), line);
+        // the top left cell goes to the top right, candidates go along
+        let mut six = Sudoku::new(6, 6, 3, 2);
+        six.set_digit(0, Digit::One);
+        assert!(six.rotate());
+        assert_eq!(*six.get_grid_dimensions().get_section_width(), 2);
+        assert_eq!(six.get_cell(5).unwrap().get_value(), &Some(Digit::One));
+        assert!(!six.get_cell(4).unwrap().has_candidate(Digit::One));
+        let mut diagonal = original.clone();
+        diagonal.add_diagonals();
+        assert!(!diagonal.transpose());
+    }
+    #[test]
+    fn test_minimality() {
+        let mut sudoku = Sudoku::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        assert!(!sudoku.is_minimal());
+        let redundant = sudoku.redundant_givens().unwrap();
+        assert!(!redundant.is_empty());
+        // taking away givens one at a time while they stay redundant ends in a minimal puzzle
+        while let Some(index) = sudoku.redundant_givens().unwrap().first().copied() {
+            assert!(redundant.contains(&index));
+            sudoku.get_mut_cell(index).unwrap().set_value(None);
+        }
+        assert!(sudoku.is_minimal());
+        assert!(sudoku.has_unique_solution());
+        let open = Sudoku::new(9, 9, 3, 3);
+        assert_eq!(open.redundant_givens(), None);
+        assert!(!open.is_minimal());
+    }
+    #[test]
+    fn test_killer_sudoku() {
+        // no givens, the cages alone fix 1234 3412 2143 4321
+        let mysudoku = Sudoku::from_killer_lines(
+            "................",
+            "aabcdebcdffgghhg",
+            &[3, 4, 6, 5, 4, 5, 8, 5],
+        )
+        .unwrap();
+        assert!(!mysudoku.is_standard());
+        assert!(mysudoku.has_unique_solution());
+        assert_eq!(
+            mysudoku.get_solution().unwrap().to_line(),
+            "1234341221434321"
+        );
+        let mut overlapping = mysudoku.clone();
+        assert!(!overlapping.add_cage(Cage::new(vec![0, 4], 4)));
+        assert!(!overlapping.add_cage(Cage::new(vec![], 0)));
+        assert!(
+            Sudoku::from_killer_lines("................", "aabcdebcdffgghhg", &[3, 9]).is_none()
+        );
     }
 }
@@ -0,0 +1,88 @@
+// making new puzzles: a random solution for the rules of a template grid, then givens are taken
+// away for as long as the solution stays unique
+pub mod random;
+
+pub use random::Random;
+
+use crate::datastructures::sudoku::Sudoku;
+
+// a random filled grid that follows all the rules and givens of template
+pub fn generate_solution(template: &Sudoku, random: &mut Random) -> Option<Sudoku> {
+    let orders = (0..template.get_grid_dimensions().get_data_size())
+        .map(|_| {
+            let mut digits = template.get_digits();
+            random.shuffle(&mut digits);
+            digits
+        })
+        .collect();
+    template.get_solution_with_order(orders)
+}
+
+// a puzzle with a unique solution for the rules of template, no given can be taken away without
+// losing that
+pub fn generate_puzzle(template: &Sudoku, random: &mut Random) -> Option<Sudoku> {
+    let mut puzzle = generate_solution(template, random)?;
+    let mut cells: Vec<usize> = puzzle
+        .cells()
+        .filter(|cell| template.get_cell(*cell.get_index()).unwrap().is_empty())
+        .map(|cell| *cell.get_index())
+        .collect();
+    random.shuffle(&mut cells);
+    for index in cells {
+        let digit = *puzzle.get_cell(index).unwrap().get_value();
+        puzzle.get_mut_cell(index).unwrap().set_value(None);
+        if !puzzle.has_unique_solution() {
+            puzzle.get_mut_cell(index).unwrap().set_value(digit);
+        }
+    }
+    puzzle.reset_candidates();
+    Some(puzzle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_puzzle() {
+        let template = Sudoku::new(9, 9, 3, 3);
+        let mut random = Random::new(2024);
+        let puzzle = generate_puzzle(&template, &mut random).unwrap();
+        assert!(puzzle.is_minimal());
+        assert!(puzzle.cells().filter(|c| !c.is_empty()).count() < 40);
+        let again = generate_puzzle(&template, &mut Random::new(2024)).unwrap();
+        assert_eq!(puzzle.to_line(), again.to_line());
+    }
+    #[test]
+    fn test_generate_windoku_solution() {
+        let mut template = Sudoku::new(9, 9, 3, 3);
+        template.add_windows();
+        let solution = generate_solution(&template, &mut Random::new(11)).unwrap();
+        assert!(solution.is_solved());
+        assert!(solution.is_consistent());
+        assert_eq!(solution.get_houses().len(), 36);
+    }
+    #[test]
+    fn test_generate_diagonal_puzzle() {
+        let mut template = Sudoku::new(9, 9, 3, 3);
+        template.add_diagonals();
+        let puzzle = generate_puzzle(&template, &mut Random::new(5)).unwrap();
+        assert!(puzzle.has_unique_solution());
+        let solution = puzzle.get_solution().unwrap();
+        assert!(solution.is_solved());
+        let diagonal: Vec<usize> = (0..9).map(|i| i * 10).collect();
+        let mut digits: Vec<usize> = diagonal
+            .iter()
+            .map(|i| {
+                solution
+                    .get_cell(*i)
+                    .unwrap()
+                    .get_value()
+                    .unwrap()
+                    .to_usize()
+            })
+            .collect();
+        digits.sort_unstable();
+        assert_eq!(digits, (1..=9).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,66 @@
+// a small xorshift generator, good enough for shuffling and reproducible from a seed
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // xorshift never leaves a zero state
+        Random {
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        }
+        .with_nonzero_state()
+    }
+    fn with_nonzero_state(mut self) -> Self {
+        if self.state == 0 {
+            self.state = 0x2545_f491_4f6c_dd1d;
+        }
+        self
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+    // a number from 0 up to but not including limit
+    pub fn below(&mut self, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+        (self.next_u64() % limit as u64) as usize
+    }
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for position in (1..items.len()).rev() {
+            let other = self.below(position + 1);
+            items.swap(position, other);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let mut first = Random::new(7);
+        let mut second = Random::new(7);
+        for _ in 0..10 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+        assert!(Random::new(8).next_u64() != Random::new(7).next_u64());
+    }
+    #[test]
+    fn test_shuffle_keeps_items() {
+        let mut random = Random::new(1);
+        let mut items: Vec<usize> = (0..20).collect();
+        random.shuffle(&mut items);
+        assert!(random.below(5) < 5);
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,155 @@
+// the files desktop solvers exchange single puzzles in: one row of the grid per line with '.' or
+// '0' for empty cells, optionally under a [Puzzle] header (SadMan Software) or after lines of
+// metadata starting with '#' and a letter (.sdk, "#Aauthor", "#Ddescription" and so on)
+// the .sdm files these programs keep collections in have a puzzle per line and are read with the
+// puzzle reader
+use crate::datastructures::sudoku::Sudoku;
+use std::io::Write;
+
+#[derive(Debug, Clone)]
+pub struct GridFile {
+    sudoku: Sudoku,
+    // letter and text of every metadata line, in the order of the file
+    metadata: Vec<(char, String)>,
+}
+
+impl GridFile {
+    pub fn new(sudoku: Sudoku, metadata: Vec<(char, String)>) -> Self {
+        GridFile { sudoku, metadata }
+    }
+    pub fn get_sudoku(&self) -> &Sudoku {
+        &self.sudoku
+    }
+    pub fn get_metadata(&self) -> &Vec<(char, String)> {
+        &self.metadata
+    }
+    // the text of the first metadata line with key
+    pub fn get_field(&self, key: char) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, text)| text.as_str())
+    }
+
+    // the first grid in text, lines of separators such as "---+---+---" and characters like '|'
+    // between the cells are passed over; sections other than [Puzzle], for example the [State]
+    // SadMan writes after it, are not read
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut metadata = Vec::new();
+        let mut rows: Vec<String> = Vec::new();
+        let mut in_puzzle = true;
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(rest) = line.strip_prefix('#') {
+                let mut characters = rest.chars();
+                if let Some(key) = characters.next() {
+                    metadata.push((key, characters.as_str().trim().to_string()));
+                }
+                continue;
+            }
+            if line.starts_with('[') {
+                in_puzzle = line.eq_ignore_ascii_case("[puzzle]");
+                continue;
+            }
+            if !in_puzzle {
+                continue;
+            }
+            let cells: String = line
+                .chars()
+                .filter(|c| c.is_ascii_digit() || *c == '.')
+                .collect();
+            if cells.is_empty() {
+                continue;
+            }
+            rows.push(cells);
+            if rows.len() == rows[0].len() {
+                break;
+            }
+        }
+        if rows.is_empty() || rows.iter().any(|row| row.len() != rows.len()) {
+            return None;
+        }
+        let sudoku = Sudoku::from_line(&rows.concat())?;
+        Some(GridFile::new(sudoku, metadata))
+    }
+
+    // the .sdk form: metadata lines, then the grid
+    pub fn to_sdk(&self) -> String {
+        let mut text = String::new();
+        for (key, value) in &self.metadata {
+            text.push_str(&format!("#{}{}\n", key, value));
+        }
+        text.push_str(&to_grid_lines(&self.sudoku));
+        text
+    }
+}
+
+// one line per row, '.' for empty cells
+pub fn to_grid_lines(sudoku: &Sudoku) -> String {
+    let size = *sudoku.get_grid_dimensions().get_column_count();
+    let line: Vec<char> = sudoku.to_line().chars().collect();
+    line.chunks(size)
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+// the SadMan Software form, the grid under a [Puzzle] header
+pub fn to_sadman(sudoku: &Sudoku) -> String {
+    format!("[Puzzle]\n{}", to_grid_lines(sudoku))
+}
+
+// puzzles one per line as in .sdm files, false when writing fails
+pub fn write_sdm<'a, W: Write>(
+    writer: &mut W,
+    puzzles: impl IntoIterator<Item = &'a Sudoku>,
+) -> bool {
+    puzzles
+        .into_iter()
+        .all(|sudoku| writeln!(writer, "{}", sudoku.to_line()).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::PuzzleReader;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_reading_grid_files() {
+        let sadman = "[Puzzle]\n53..7....\n6..195...\n.98....6.\n8...6...3\n4..8.3..1\n7...2...6\n.6....28.\n...419..5\n....8..79\n[State]\n534678912\n";
+        let grid = GridFile::parse(sadman).unwrap();
+        assert_eq!(grid.get_sudoku().to_line(), PUZZLE);
+        assert!(grid.get_metadata().is_empty());
+        let sdk = "#AJane Doe\n#DA classic\n530|070|000\n600|195|000\n098|000|060\n---+---+---\n800|060|003\n400|803|001\n700|020|006\n---+---+---\n060|000|280\n000|419|005\n000|080|079\n";
+        let grid = GridFile::parse(sdk).unwrap();
+        assert_eq!(grid.get_sudoku().to_line(), PUZZLE);
+        assert_eq!(grid.get_field('A'), Some("Jane Doe"));
+        assert_eq!(grid.get_field('D'), Some("A classic"));
+        assert_eq!(grid.get_field('C'), None);
+        assert!(GridFile::parse("53..7....\n6..195...\n").is_none());
+    }
+    #[test]
+    fn test_writing_grid_files() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let grid = GridFile::new(sudoku.clone(), vec![('A', String::from("Jane Doe"))]);
+        let sdk = grid.to_sdk();
+        assert!(sdk.starts_with("#AJane Doe\n53..7....\n"));
+        let again = GridFile::parse(&sdk).unwrap();
+        assert_eq!(again.get_sudoku().to_line(), PUZZLE);
+        assert_eq!(again.get_metadata(), grid.get_metadata());
+        let sadman = to_sadman(&sudoku);
+        assert_eq!(sadman.lines().count(), 10);
+        assert_eq!(
+            GridFile::parse(&sadman).unwrap().get_sudoku().to_line(),
+            PUZZLE
+        );
+        let mut sdm = Vec::new();
+        assert!(write_sdm(&mut sdm, [&sudoku, &sudoku]));
+        let read: Vec<String> = PuzzleReader::new(sdm.as_slice())
+            .map(|entry| entry.unwrap().get_sudoku().to_line())
+            .collect();
+        assert_eq!(read, vec![PUZZLE, PUZZLE]);
+    }
+}
@@ -0,0 +1,8 @@
+// reading and writing puzzles in the file formats other programs use
+pub mod grid_file;
+pub mod pencil_marks;
+pub mod reader;
+
+pub use grid_file::GridFile;
+pub use pencil_marks::{parse_pencil_marks, to_pencil_marks};
+pub use reader::{PuzzleEntry, PuzzleReader};
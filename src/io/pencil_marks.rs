@@ -0,0 +1,212 @@
+// the pencil mark grid: every cell shows its value or the candidates left in it, sections are
+// separated by '|' and by lines of '-' and '+'
+//
+// +-------------+----------+
+// | 5   3   124 | 26  7  2 |
+//
+// a single digit is a placed value, an empty cell with one candidate left has it in brackets, as
+// "(4)", so the two stay apart; the section size follows from where the borders are
+use crate::datastructures::digit::{Digit, DigitHashSet};
+use crate::datastructures::sudoku::Sudoku;
+
+// the digits of a cell, "." for an empty cell without candidates and brackets around the only
+// candidate of an empty cell
+fn cell_text(sudoku: &Sudoku, index: usize) -> String {
+    let cell = sudoku.get_cell(index).unwrap();
+    if let Some(digit) = cell.get_value() {
+        return digit.to_char().to_string();
+    }
+    let mut digits: Vec<Digit> = cell.get_candidates().iter().copied().collect();
+    if digits.is_empty() {
+        return String::from(".");
+    }
+    if digits.len() == 1 {
+        return format!("({})", digits[0].to_char());
+    }
+    digits.sort_by_key(|d| d.to_usize());
+    digits.iter().map(|d| d.to_char()).collect()
+}
+
+pub fn to_pencil_marks(sudoku: &Sudoku) -> String {
+    let dimensions = sudoku.get_grid_dimensions();
+    let size = *dimensions.get_column_count();
+    let (width, height) = (
+        *dimensions.get_section_width(),
+        *dimensions.get_section_height(),
+    );
+    let texts: Vec<String> = (0..dimensions.get_data_size())
+        .map(|index| cell_text(sudoku, index))
+        .collect();
+    // every column as wide as its widest cell
+    let widths: Vec<usize> = (0..size)
+        .map(|column| {
+            (0..size)
+                .map(|row| texts[row * size + column].len())
+                .max()
+                .unwrap_or(1)
+        })
+        .collect();
+    let border: String = widths
+        .chunks(width)
+        .map(|group| {
+            let inner: usize = group.iter().sum::<usize>() + 2 * (group.len() - 1);
+            format!("+{}", "-".repeat(inner + 2))
+        })
+        .collect::<String>()
+        + "+\n";
+    let mut result = String::new();
+    for row in 0..size {
+        if row % height == 0 {
+            result.push_str(&border);
+        }
+        for column in 0..size {
+            if column % width == 0 {
+                result.push_str(if column == 0 { "| " } else { " | " });
+            } else {
+                result.push_str("  ");
+            }
+            result.push_str(&format!(
+                "{:<w$}",
+                texts[row * size + column],
+                w = widths[column]
+            ));
+        }
+        result.push_str(" |\n");
+    }
+    result.push_str(&border);
+    result
+}
+
+// the cells of a line of the grid, None for a border line
+fn row_tokens(line: &str) -> Option<Vec<&str>> {
+    let tokens: Vec<&str> = line
+        .split(|c: char| c == '|' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .collect();
+    let is_cell = |token: &&str| {
+        let digits = token
+            .strip_prefix('(')
+            .and_then(|rest| rest.strip_suffix(')'))
+            .unwrap_or(token);
+        *token == "." || (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+    };
+    if tokens.is_empty() || !tokens.iter().all(is_cell) {
+        return None;
+    }
+    Some(tokens)
+}
+
+pub fn parse_pencil_marks(text: &str) -> Option<Sudoku> {
+    let mut rows: Vec<Vec<&str>> = Vec::new();
+    let mut width = None;
+    let mut height = None;
+    for line in text.lines() {
+        match row_tokens(line) {
+            Some(tokens) => {
+                if width.is_none() {
+                    let groups: Vec<&str> = line
+                        .split('|')
+                        .filter(|group| !group.trim().is_empty())
+                        .collect();
+                    if groups.len() > 1 {
+                        width = Some(groups[0].split_whitespace().count());
+                    }
+                }
+                rows.push(tokens);
+            }
+            None => {
+                if height.is_none() && !rows.is_empty() {
+                    height = Some(rows.len());
+                }
+            }
+        }
+    }
+    let size = rows.len();
+    let width = width.unwrap_or(size);
+    let height = height.filter(|h| *h < size).unwrap_or(size);
+    if size == 0 || size > 9 || width * height != size || rows.iter().any(|r| r.len() != size) {
+        return None;
+    }
+    let mut sudoku = Sudoku::new(size, size, width, height);
+    let mut marks: Vec<(usize, DigitHashSet)> = Vec::new();
+    for (index, token) in rows.concat().iter().enumerate() {
+        let mut candidates = DigitHashSet::new();
+        for character in token.chars().filter(|c| !matches!(c, '.' | '(' | ')')) {
+            candidates.insert(Digit::from_char(character).filter(|d| d.to_usize() <= size)?);
+        }
+        if token.len() == 1 && !candidates.is_empty() {
+            sudoku
+                .get_mut_cell(index)
+                .unwrap()
+                .set_value(candidates.into_iter().next());
+        } else {
+            marks.push((index, candidates));
+        }
+    }
+    sudoku.reset_candidates();
+    for (index, candidates) in marks {
+        sudoku
+            .get_mut_cell(index)
+            .unwrap()
+            .set_candidates(candidates);
+    }
+    Some(sudoku)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pencil_marks_round_trip() {
+        let mut sudoku = Sudoku::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        sudoku.remove_candidate(7, Digit::Nine);
+        let text = to_pencil_marks(&sudoku);
+        assert_eq!(text.lines().count(), 13);
+        assert!(text.starts_with("+-------------------+---"));
+        assert_eq!(
+            text.lines().nth(1).unwrap(),
+            "| 5    3     124    | 26    7    2468 | 1489   124   248  |"
+        );
+        // r5c5 is empty with five as its only candidate
+        assert!(text.lines().nth(6).unwrap().contains("(5)"));
+        let again = parse_pencil_marks(&text).unwrap();
+        for index in 0..81 {
+            let cell = sudoku.get_cell(index).unwrap();
+            let other = again.get_cell(index).unwrap();
+            assert_eq!(other.get_value(), cell.get_value());
+            if cell.is_empty() {
+                assert_eq!(other.get_candidates(), cell.get_candidates());
+            }
+        }
+        assert!(again.get_cell(40).unwrap().is_empty());
+        assert_eq!(again.get_cell(40).unwrap().get_candidates().len(), 1);
+        assert_eq!(to_pencil_marks(&again), text);
+    }
+    #[test]
+    fn test_parse_pencil_marks_of_a_six_grid() {
+        let text = "\
+.---------------.---------------.
+| 1   23  456  | 2   3   4    |
+| 23  4   5    | 1   6   .    |
+:---------------+---------------:
+| 3   4   5    | 6   1   2    |
+| 4   5   6    | 3   2   1    |
+:---------------+---------------:
+| 5   6   1    | 4   3   2    |
+| 6   1   2    | 5   4   3    |
+'---------------'---------------'
+";
+        let sudoku = parse_pencil_marks(text).unwrap();
+        assert_eq!(*sudoku.get_grid_dimensions().get_section_width(), 3);
+        assert_eq!(*sudoku.get_grid_dimensions().get_section_height(), 2);
+        assert_eq!(sudoku.get_cell(0).unwrap().get_value(), &Some(Digit::One));
+        assert_eq!(sudoku.get_cell(1).unwrap().get_candidates().len(), 2);
+        assert!(sudoku.get_cell(11).unwrap().get_candidates().is_empty());
+        assert!(parse_pencil_marks("| 1 2 |\n| 3 |\n").is_none());
+        assert!(parse_pencil_marks("| 1 7 |\n| 2 1 |\n").is_none());
+    }
+}
@@ -0,0 +1,210 @@
+// collections of puzzles, one per line: the givens first, then optionally whatever the file
+// keeps about the puzzle (a rating, a name, a source) after whitespace or a '#'
+// blank lines and lines starting with '#' are skipped; lines are read one at a time so files of
+// any size can be gone through, bytes that are not utf-8 are replaced rather than stopping there
+use crate::datastructures::sudoku::Sudoku;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Split};
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct PuzzleEntry {
+    // counting from one, as editors do
+    line_number: usize,
+    sudoku: Sudoku,
+    info: String,
+}
+
+impl PuzzleEntry {
+    pub fn get_line_number(&self) -> &usize {
+        &self.line_number
+    }
+    pub fn get_sudoku(&self) -> &Sudoku {
+        &self.sudoku
+    }
+    pub fn into_sudoku(self) -> Sudoku {
+        self.sudoku
+    }
+    // the rest of the line after the puzzle, trimmed
+    pub fn get_info(&self) -> &str {
+        &self.info
+    }
+    // the first word of the info when it is a number, most collections put their rating there
+    pub fn get_rating(&self) -> Option<f64> {
+        self.info.split_whitespace().next()?.parse().ok()
+    }
+}
+
+pub struct PuzzleReader<R: BufRead> {
+    lines: Split<R>,
+    line_number: usize,
+    // lines that were neither comments nor puzzles, or could not be read
+    skipped: Vec<usize>,
+}
+
+impl<R: BufRead> PuzzleReader<R> {
+    pub fn new(reader: R) -> Self {
+        PuzzleReader {
+            lines: reader.split(b'\n'),
+            line_number: 0,
+            skipped: Vec::new(),
+        }
+    }
+    pub fn get_skipped(&self) -> &Vec<usize> {
+        &self.skipped
+    }
+}
+
+impl PuzzleReader<BufReader<File>> {
+    pub fn open<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        Some(PuzzleReader::new(BufReader::new(file)))
+    }
+}
+
+// the puzzle of a line and what follows it, None for blank and comment lines
+fn split_line(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == '#')
+        .unwrap_or(line.len());
+    let info = line[end..].trim_start().trim_start_matches('#').trim();
+    Some((&line[..end], info))
+}
+
+impl<R: BufRead> Iterator for PuzzleReader<R> {
+    type Item = io::Result<PuzzleEntry>;
+
+    // a line that can not be read is handed on as the error, reading goes on after it
+    fn next(&mut self) -> Option<io::Result<PuzzleEntry>> {
+        loop {
+            let bytes = self.lines.next()?;
+            self.line_number += 1;
+            let bytes = match bytes {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    self.skipped.push(self.line_number);
+                    return Some(Err(error));
+                }
+            };
+            let line = String::from_utf8_lossy(&bytes);
+            let (puzzle, info) = match split_line(&line) {
+                Some(parts) => parts,
+                None => continue,
+            };
+            match Sudoku::from_line(puzzle) {
+                Some(sudoku) => {
+                    return Some(Ok(PuzzleEntry {
+                        line_number: self.line_number,
+                        sudoku,
+                        info: info.to_string(),
+                    }))
+                }
+                None => self.skipped.push(self.line_number),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::Digit;
+    use std::io::Read;
+
+    const COLLECTION: &str = "# a few puzzles
+53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79 1.5 first
+
+..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..#newspaper
+not a puzzle
+1234341221434321
+";
+
+    #[test]
+    fn test_reading_a_collection() {
+        let mut reader = PuzzleReader::new(COLLECTION.as_bytes());
+        let entries: Vec<PuzzleEntry> = reader.by_ref().map(Result::unwrap).collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].get_line_number(), &2);
+        assert_eq!(entries[0].get_rating(), Some(1.5));
+        assert_eq!(entries[0].get_info(), "1.5 first");
+        assert_eq!(entries[1].get_info(), "newspaper");
+        assert_eq!(entries[1].get_rating(), None);
+        assert_eq!(
+            entries[1].get_sudoku().get_cell(2).unwrap().get_value(),
+            &Some(Digit::Three)
+        );
+        assert_eq!(
+            entries[2]
+                .get_sudoku()
+                .get_grid_dimensions()
+                .get_data_size(),
+            16
+        );
+        assert_eq!(reader.get_skipped(), &vec![5]);
+    }
+    #[test]
+    fn test_reading_past_bad_lines() {
+        let mut bytes = b"# source: ".to_vec();
+        bytes.extend([0xff, 0xfe, b'\n']);
+        bytes.extend(b"1234341221434321 caf");
+        bytes.extend([0xe9, b'\n']);
+        bytes.extend(b"2143341212344321\n");
+        let entries: Vec<PuzzleEntry> = PuzzleReader::new(bytes.as_slice())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].get_info(), "caf\u{fffd}");
+        assert_eq!(entries[1].get_line_number(), &3);
+        // a read that fails once in the middle of the file
+        let failing = BufReader::new(Failing(b"1234341221434321\n", 0));
+        let mut reader = PuzzleReader::new(failing);
+        let results: Vec<io::Result<PuzzleEntry>> = reader.by_ref().take(3).collect();
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+        assert_eq!(reader.get_skipped(), &vec![2]);
+    }
+    #[test]
+    fn test_reading_is_lazy() {
+        // an endless source of the same puzzle
+        let line = b"1234341221434321\n";
+        let endless = BufReader::new(Cycle(line, 0));
+        let count = PuzzleReader::new(endless).take(1000).count();
+        assert_eq!(count, 1000);
+    }
+
+    // the bytes, then an error, then the bytes again and the end
+    struct Failing(&'static [u8], usize);
+
+    impl Read for Failing {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            self.1 += 1;
+            match self.1 {
+                1 | 3 => {
+                    buffer[..self.0.len()].copy_from_slice(self.0);
+                    Ok(self.0.len())
+                }
+                2 => Err(io::Error::other("disk gone")),
+                _ => Ok(0),
+            }
+        }
+    }
+
+    struct Cycle(&'static [u8], usize);
+
+    impl Read for Cycle {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let mut written = 0;
+            while written < buffer.len() {
+                buffer[written] = self.0[self.1];
+                self.1 = (self.1 + 1) % self.0.len();
+                written += 1;
+            }
+            Ok(written)
+        }
+    }
+}
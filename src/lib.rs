@@ -1,4 +1,10 @@
+pub mod analysis;
 pub mod datastructures;
+pub mod generating;
+pub mod io;
+pub mod output;
+pub mod solving;
+pub mod symmetry;
 
 
 #[cfg(test)]
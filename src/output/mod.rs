@@ -0,0 +1,40 @@
+// drawing grids for people: a plain text version and an svg picture
+// sections are outlined by following the section of every cell, so irregular ones work as well;
+// the cells a layout leaves unused count as outside the grid
+pub mod svg;
+pub mod text;
+
+use crate::datastructures::sudoku::Sudoku;
+
+// the section of the cell at row and column, None outside the grid or for an unused cell
+fn section_at(sudoku: &Sudoku, row: Option<usize>, column: Option<usize>) -> Option<usize> {
+    let dimensions = sudoku.get_grid_dimensions();
+    let (row, column) = (row?, column?);
+    if row >= *dimensions.get_row_count() || column >= *dimensions.get_column_count() {
+        return None;
+    }
+    let index = row * dimensions.get_column_count() + column;
+    if !sudoku.is_active(index) {
+        return None;
+    }
+    Some(*sudoku.get_cell(index).unwrap().get_section())
+}
+
+// whether the cell at row and column is in use, false outside the grid
+pub fn is_used(sudoku: &Sudoku, row: Option<usize>, column: Option<usize>) -> bool {
+    section_at(sudoku, row, column).is_some()
+}
+
+// whether a section border runs along the left of the cell at row and column, column can be
+// one past the last to ask for the right edge of the grid
+pub fn vertical_border(sudoku: &Sudoku, row: usize, column: usize) -> bool {
+    section_at(sudoku, Some(row), column.checked_sub(1))
+        != section_at(sudoku, Some(row), Some(column))
+}
+
+// whether a section border runs along the top of the cell at row and column, row can be one past
+// the last to ask for the bottom edge of the grid
+pub fn horizontal_border(sudoku: &Sudoku, row: usize, column: usize) -> bool {
+    section_at(sudoku, row.checked_sub(1), Some(column))
+        != section_at(sudoku, Some(row), Some(column))
+}
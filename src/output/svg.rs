@@ -0,0 +1,347 @@
+// the grid as an svg picture: thin lines between cells, thick ones along section borders
+use super::{horizontal_border, is_used, vertical_border};
+use crate::datastructures::constraint::Marking;
+use crate::datastructures::house::HouseKind;
+use crate::datastructures::sudoku::Sudoku;
+
+pub const CELL_SIZE: usize = 40;
+// room around the grid, clues outside the grid are drawn there
+pub const MARGIN: usize = 40;
+
+fn line(x1: usize, y1: usize, x2: usize, y2: usize, width: usize) -> String {
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" stroke-width=\"{}\" stroke-linecap=\"square\"/>",
+        x1, y1, x2, y2, width
+    )
+}
+
+// the top left corner of a cell in the picture
+pub fn cell_position(sudoku: &Sudoku, index: usize) -> (usize, usize) {
+    let cell = sudoku.get_cell(index).unwrap();
+    (
+        MARGIN + cell.get_column() * CELL_SIZE,
+        MARGIN + cell.get_row() * CELL_SIZE,
+    )
+}
+
+fn centre(sudoku: &Sudoku, index: usize) -> (usize, usize) {
+    let (x, y) = cell_position(sudoku, index);
+    (x + CELL_SIZE / 2, y + CELL_SIZE / 2)
+}
+
+// text for inside an svg element
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// the middle of the edge between two cells next to each other
+fn edge_centre(sudoku: &Sudoku, cells: (usize, usize)) -> (usize, usize) {
+    let (first_x, first_y) = centre(sudoku, cells.0);
+    let (second_x, second_y) = centre(sudoku, cells.1);
+    ((first_x + second_x) / 2, (first_y + second_y) / 2)
+}
+
+fn polyline(sudoku: &Sudoku, cells: &[usize], color: &str, width: usize) -> String {
+    let points: Vec<String> = cells
+        .iter()
+        .map(|index| {
+            let (x, y) = centre(sudoku, *index);
+            format!("{},{}", x, y)
+        })
+        .collect();
+    format!(
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>",
+        points.join(" "),
+        color,
+        width
+    )
+}
+
+// the shapes for the markings of a rule
+fn marking_parts(sudoku: &Sudoku, marking: &Marking) -> Vec<String> {
+    match marking {
+        Marking::Line { cells, color } => vec![polyline(sudoku, cells, color, CELL_SIZE / 4)],
+        Marking::Arrow { cells } => {
+            let mut parts = vec![polyline(sudoku, cells, "gray", 2)];
+            if cells.len() >= 2 {
+                // two short strokes back from the tip, at an angle to the last part of the arrow
+                let (tip_x, tip_y) = centre(sudoku, cells[cells.len() - 1]);
+                let (from_x, from_y) = centre(sudoku, cells[cells.len() - 2]);
+                let (dx, dy) = (tip_x as f64 - from_x as f64, tip_y as f64 - from_y as f64);
+                let length = (dx * dx + dy * dy).sqrt();
+                let (ux, uy) = (dx / length, dy / length);
+                let size = CELL_SIZE as f64 / 4.0;
+                for side in [-1.0, 1.0] {
+                    let x = tip_x as f64 - size * (ux - side * uy);
+                    let y = tip_y as f64 - size * (uy + side * ux);
+                    parts.push(format!(
+                        "<line x1=\"{}\" y1=\"{}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"gray\" stroke-width=\"2\" stroke-linecap=\"round\"/>",
+                        tip_x, tip_y, x, y
+                    ));
+                }
+            }
+            parts
+        }
+        Marking::Circle { cell, filled } => {
+            let (x, y) = centre(sudoku, *cell);
+            let fill = if *filled { "lightgray" } else { "white" };
+            vec![format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"gray\" stroke-width=\"2\"/>",
+                x,
+                y,
+                CELL_SIZE * 2 / 5,
+                fill
+            )]
+        }
+        Marking::Shade { cell, round } => {
+            let (x, y) = cell_position(sudoku, *cell);
+            if *round {
+                vec![format!(
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"lightgray\"/>",
+                    x + CELL_SIZE / 2,
+                    y + CELL_SIZE / 2,
+                    CELL_SIZE * 2 / 5
+                )]
+            } else {
+                vec![format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"lightgray\"/>",
+                    x + CELL_SIZE / 10,
+                    y + CELL_SIZE / 10,
+                    CELL_SIZE * 4 / 5,
+                    CELL_SIZE * 4 / 5
+                )]
+            }
+        }
+        Marking::Dot { cells, filled } => {
+            let (x, y) = edge_centre(sudoku, *cells);
+            let fill = if *filled { "black" } else { "white" };
+            vec![format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"black\" stroke-width=\"1\"/>",
+                x,
+                y,
+                CELL_SIZE / 8,
+                fill
+            )]
+        }
+        Marking::Outside {
+            position,
+            text,
+            direction,
+        } => {
+            // the margin is one cell wide, so the clue gets a cell of its own
+            let x = MARGIN as isize + position.1 * CELL_SIZE as isize + CELL_SIZE as isize / 2;
+            let y = MARGIN as isize + position.0 * CELL_SIZE as isize + CELL_SIZE as isize / 2;
+            let mut parts = vec![format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                x,
+                y,
+                CELL_SIZE * 2 / 5,
+                text
+            )];
+            if let Some((row, column)) = direction {
+                // a short arrow from the corner of the clue's cell that faces the diagonal
+                let quarter = CELL_SIZE as isize / 4;
+                let (start_x, start_y) = (x + column * quarter, y + row * quarter);
+                let (end_x, end_y) = (start_x + column * quarter, start_y + row * quarter);
+                parts.push(format!(
+                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" stroke-width=\"1\" stroke-linecap=\"round\"/>",
+                    start_x, start_y, end_x, end_y
+                ));
+            }
+            parts
+        }
+        Marking::Label { cells, text } => {
+            let (x, y) = edge_centre(sudoku, *cells);
+            vec![format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" stroke=\"white\" stroke-width=\"3\" paint-order=\"stroke\">{}</text>",
+                x,
+                y,
+                CELL_SIZE * 2 / 5,
+                escape(text)
+            )]
+        }
+    }
+}
+
+pub fn to_svg(sudoku: &Sudoku) -> String {
+    let dimensions = sudoku.get_grid_dimensions();
+    let (rows, columns) = (*dimensions.get_row_count(), *dimensions.get_column_count());
+    let width = columns * CELL_SIZE + 2 * MARGIN;
+    let height = rows * CELL_SIZE + 2 * MARGIN;
+    let mut parts = vec![
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            width, height, width, height
+        ),
+        format!(
+            "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"white\"/>",
+            width, height
+        ),
+    ];
+    // the windows of a hyper sudoku are shaded, the regions they imply are not drawn
+    for house in sudoku.get_houses() {
+        if house.get_kind() != &HouseKind::Window {
+            continue;
+        }
+        for index in house.get_cells() {
+            let (x, y) = cell_position(sudoku, *index);
+            parts.push(format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"lightgray\"/>",
+                x, y, CELL_SIZE, CELL_SIZE
+            ));
+        }
+    }
+    // marks on the edges between cells go on top of the grid lines, the rest below
+    let mut on_top = Vec::new();
+    for constraint in sudoku.get_constraints() {
+        for marking in constraint.get_markings() {
+            match marking {
+                Marking::Dot { .. } | Marking::Label { .. } => {
+                    on_top.extend(marking_parts(sudoku, &marking))
+                }
+                _ => parts.extend(marking_parts(sudoku, &marking)),
+            }
+        }
+    }
+    let mut borders = Vec::new();
+    for row in 0..=rows {
+        for column in 0..=columns {
+            let (x, y) = (MARGIN + column * CELL_SIZE, MARGIN + row * CELL_SIZE);
+            // no thin lines between unused cells
+            let above = is_used(sudoku, row.checked_sub(1), Some(column));
+            let left = is_used(sudoku, Some(row), column.checked_sub(1));
+            let here = is_used(sudoku, Some(row), Some(column));
+            if column < columns && (above || here) {
+                let thick = horizontal_border(sudoku, row, column);
+                let part = line(x, y, x + CELL_SIZE, y, if thick { 3 } else { 1 });
+                if thick {
+                    borders.push(part);
+                } else {
+                    parts.push(part);
+                }
+            }
+            if row < rows && (left || here) {
+                let thick = vertical_border(sudoku, row, column);
+                let part = line(x, y, x, y + CELL_SIZE, if thick { 3 } else { 1 });
+                if thick {
+                    borders.push(part);
+                } else {
+                    parts.push(part);
+                }
+            }
+        }
+    }
+    // thick lines last so thin ones never cover them
+    parts.extend(borders);
+    parts.extend(on_top);
+    for cell in sudoku.cells() {
+        if let Some(digit) = cell.get_value() {
+            let (x, y) = cell_position(sudoku, *cell.get_index());
+            parts.push(format!(
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                x + CELL_SIZE / 2,
+                y + CELL_SIZE / 2,
+                CELL_SIZE * 3 / 5,
+                digit.to_usize()
+            ));
+        }
+    }
+    parts.push(String::from("</svg>"));
+    parts.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::comparison::{Comparison, Parity};
+    use crate::datastructures::edges::{EdgeClue, EdgeKind};
+    use crate::datastructures::layout::Layout;
+    use crate::datastructures::lines::{Arrow, Palindrome, Thermometer};
+    use crate::datastructures::outside::OutsideClue;
+
+    #[test]
+    fn test_svg_of_a_standard_grid() {
+        let sudoku = Sudoku::from_line("1234341221434321").unwrap();
+        let svg = to_svg(&sudoku);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<text").count(), 16);
+        // the outline and the two lines through the middle, two cells long each
+        assert_eq!(svg.matches("stroke-width=\"3\"").count(), 24);
+        assert_eq!(svg.matches("stroke-width=\"1\"").count(), 16);
+    }
+    #[test]
+    fn test_svg_follows_jigsaw_sections() {
+        let sudoku = Sudoku::from_jigsaw_lines("1...............", "0001201122312333").unwrap();
+        let svg = to_svg(&sudoku);
+        assert_eq!(svg.matches("<text").count(), 1);
+        assert_eq!(svg.matches("stroke-width=\"3\"").count(), 16 + 12);
+    }
+    #[test]
+    fn test_svg_of_a_samurai_layout() {
+        let sudoku = Sudoku::from_layout(&Layout::samurai());
+        let svg = to_svg(&sudoku);
+        assert_eq!(svg.matches("<text").count(), 0);
+        // twelve thin lines inside each of the 41 sections, none in the unused corners
+        assert_eq!(svg.matches("stroke-width=\"1\"").count(), 41 * 12);
+    }
+    #[test]
+    fn test_svg_draws_lines() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(Thermometer::new(vec![0, 1, 2])));
+        sudoku.add_constraint(Box::new(Arrow::new(40, vec![41, 42])));
+        sudoku.add_constraint(Box::new(Palindrome::new(vec![72, 73, 74])));
+        let svg = to_svg(&sudoku);
+        assert_eq!(svg.matches("<polyline").count(), 3);
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(svg.contains("points=\"60,60 100,60 140,60\""));
+        // the arrow head on r5c7
+        assert_eq!(svg.matches("<line x1=\"300\" y1=\"220\"").count(), 2);
+    }
+    #[test]
+    fn test_svg_draws_edge_clues() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(EdgeClue::new(EdgeKind::WhiteDot, 0, 1)));
+        sudoku.add_constraint(Box::new(EdgeClue::new(EdgeKind::BlackDot, 0, 9)));
+        sudoku.add_constraint(Box::new(EdgeClue::new(EdgeKind::X, 2, 3)));
+        let svg = to_svg(&sudoku);
+        assert!(svg.contains("<circle cx=\"80\" cy=\"60\" r=\"5\" fill=\"white\""));
+        assert!(svg.contains("<circle cx=\"60\" cy=\"80\" r=\"5\" fill=\"black\""));
+        assert!(svg.contains(">X</text>"));
+        // after the thick line it sits on
+        assert!(svg.rfind("stroke-width=\"3\"").unwrap() < svg.find(">X</text>").unwrap());
+    }
+    #[test]
+    fn test_svg_draws_outside_clues() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        let clue = OutsideClue::parse(&dimensions, "sandwich top 1 15").unwrap();
+        sudoku.add_constraint(Box::new(clue));
+        let clue = OutsideClue::parse(&dimensions, "little-killer left 9 up-right 12").unwrap();
+        sudoku.add_constraint(Box::new(clue));
+        let svg = to_svg(&sudoku);
+        assert!(svg.contains("<text x=\"60\" y=\"20\" font-size=\"16\" text-anchor=\"middle\" dominant-baseline=\"central\">15</text>"));
+        assert!(svg.contains(">12</text>"));
+        assert!(svg.contains("<line x1=\"30\" y1=\"370\" x2=\"40\" y2=\"360\""));
+    }
+    #[test]
+    fn test_svg_draws_parity_and_signs() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(Parity::new(vec![0, 1], true)));
+        sudoku.add_constraint(Box::new(Parity::new(vec![2], false)));
+        sudoku.add_constraint(Box::new(Comparison::new(4, 3)));
+        let svg = to_svg(&sudoku);
+        assert_eq!(svg.matches("<rect x=\"44\"").count(), 1);
+        assert_eq!(svg.matches("fill=\"lightgray\"").count(), 3);
+        assert!(svg.contains(">&gt;</text>"));
+    }
+    #[test]
+    fn test_svg_shades_windows() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_windows();
+        let svg = to_svg(&sudoku);
+        assert_eq!(svg.matches("fill=\"lightgray\"").count(), 4 * 9);
+    }
+}
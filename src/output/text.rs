@@ -0,0 +1,121 @@
+// the grid as text, section borders are drawn with | and - and empty cells are dots; cells a
+// layout leaves unused stay blank; rules that have markings are listed under the grid
+use super::{horizontal_border, vertical_border};
+use crate::datastructures::sudoku::Sudoku;
+use crate::solving::format_cells;
+
+fn corner(sudoku: &Sudoku, row: usize, column: usize) -> char {
+    let dimensions = sudoku.get_grid_dimensions();
+    let (rows, columns) = (*dimensions.get_row_count(), *dimensions.get_column_count());
+    let up = row > 0 && vertical_border(sudoku, row - 1, column);
+    let down = row < rows && vertical_border(sudoku, row, column);
+    let left = column > 0 && horizontal_border(sudoku, row, column - 1);
+    let right = column < columns && horizontal_border(sudoku, row, column);
+    match (up || down, left || right) {
+        (true, true) => '+',
+        (true, false) => '|',
+        (false, true) => '-',
+        (false, false) => ' ',
+    }
+}
+
+pub fn to_text(sudoku: &Sudoku) -> String {
+    let dimensions = sudoku.get_grid_dimensions();
+    let (rows, columns) = (*dimensions.get_row_count(), *dimensions.get_column_count());
+    let mut lines: Vec<String> = Vec::new();
+    for row in 0..=rows {
+        let mut border = String::new();
+        for column in 0..=columns {
+            border.push(corner(sudoku, row, column));
+            if column < columns {
+                border.push_str(if horizontal_border(sudoku, row, column) {
+                    "---"
+                } else {
+                    "   "
+                });
+            }
+        }
+        lines.push(border);
+        if row == rows {
+            break;
+        }
+        let mut cells = String::new();
+        for column in 0..=columns {
+            cells.push(if vertical_border(sudoku, row, column) {
+                '|'
+            } else {
+                ' '
+            });
+            if column < columns {
+                let index = row * columns + column;
+                let cell = sudoku.get_cell(index).unwrap();
+                cells.push(' ');
+                if sudoku.is_active(index) {
+                    cells.push(cell.get_value().map_or('.', |d| d.to_char()));
+                } else {
+                    cells.push(' ');
+                }
+                cells.push(' ');
+            }
+        }
+        lines.push(cells);
+    }
+    for constraint in sudoku.get_constraints() {
+        if constraint.get_markings().is_empty() {
+            continue;
+        }
+        lines.push(format!(
+            "{}: {}",
+            constraint.get_name(),
+            format_cells(sudoku, &constraint.get_cells())
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::lines::{Arrow, Thermometer};
+
+    #[test]
+    fn test_text_of_a_standard_grid() {
+        let sudoku = Sudoku::from_line("1234341221434321").unwrap();
+        let expected = "\
++-------+-------+
+| 1   2 | 3   4 |
+|       |       |
+| 3   4 | 1   2 |
++-------+-------+
+| 2   1 | 4   3 |
+|       |       |
+| 4   3 | 2   1 |
++-------+-------+
+";
+        assert_eq!(to_text(&sudoku), expected);
+    }
+    #[test]
+    fn test_text_lists_lines() {
+        let mut sudoku = Sudoku::from_line("1234341221434321").unwrap();
+        sudoku.add_constraint(Box::new(Thermometer::new(vec![0, 1, 2])));
+        sudoku.add_constraint(Box::new(Arrow::new(3, vec![6, 5])));
+        let text = to_text(&sudoku);
+        assert!(text.ends_with("thermometer: r1c1,r1c2,r1c3\narrow: r1c4,r2c3,r2c2\n"));
+    }
+    #[test]
+    fn test_text_of_a_jigsaw_grid() {
+        let sudoku = Sudoku::from_jigsaw_lines("1...............", "0001201122312333").unwrap();
+        let expected = "\
++-----------+---+
+| 1   .   . | . |
++---+   +---+   |
+| . | . | .   . |
+|   +---+---+   |
+| .   . | . | . |
+|   +---+   +---+
+| . | .   .   . |
++---+-----------+
+";
+        assert_eq!(to_text(&sudoku), expected);
+    }
+}
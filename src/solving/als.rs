@@ -0,0 +1,555 @@
+// almost locked sets: n empty cells in one house with n + 1 candidates between them, taking one
+// digit away from an almost locked set turns it into a locked set
+use super::{
+    candidates, combinations, format_cells, format_digits, section_line_pairs, Step, Technique,
+};
+use crate::datastructures::digit::{Digit, DigitHashSet};
+use crate::datastructures::sudoku::Sudoku;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Als {
+    cells: Vec<usize>,
+    digits: DigitHashSet,
+    // for every digit, the cells of the set that can hold it
+    holding: HashMap<Digit, Vec<usize>>,
+    // for every digit, the empty cells outside the set that see all the cells holding it
+    seen: HashMap<Digit, Vec<usize>>,
+}
+
+impl Als {
+    fn new(sudoku: &Sudoku, cells: Vec<usize>, digits: DigitHashSet) -> Self {
+        let mut holding = HashMap::new();
+        let mut seen = HashMap::new();
+        for digit in digits.iter().copied() {
+            let with_digit: Vec<usize> = cells
+                .iter()
+                .copied()
+                .filter(|c| candidates(sudoku, *c).contains(&digit))
+                .collect();
+            let seeing: Vec<usize> = sudoku
+                .cells()
+                .filter(|c| c.is_empty() && !cells.contains(c.get_index()))
+                .map(|c| *c.get_index())
+                .filter(|i| with_digit.iter().all(|c| sudoku.sees(*i, *c)))
+                .collect();
+            holding.insert(digit, with_digit);
+            seen.insert(digit, seeing);
+        }
+        Als {
+            cells,
+            digits,
+            holding,
+            seen,
+        }
+    }
+    pub fn get_cells(&self) -> &Vec<usize> {
+        &self.cells
+    }
+    pub fn get_digits(&self) -> &DigitHashSet {
+        &self.digits
+    }
+    // the cells of the set that can hold digit
+    pub fn cells_with(&self, digit: Digit) -> &[usize] {
+        self.holding.get(&digit).map_or(&[], |cells| cells)
+    }
+    fn seen_with(&self, digit: Digit) -> &[usize] {
+        self.seen.get(&digit).map_or(&[], |cells| cells)
+    }
+    pub fn overlaps(&self, other: &Als) -> bool {
+        self.cells.iter().any(|c| other.cells.contains(c))
+    }
+    pub fn format(&self, sudoku: &Sudoku) -> String {
+        format!(
+            "{} {{{}}}",
+            format_cells(sudoku, &self.cells),
+            format_digits(&self.digits)
+        )
+    }
+}
+
+// every almost locked set in the rows, columns and sections, smallest first
+pub fn find_all_als(sudoku: &Sudoku) -> Vec<Als> {
+    let mut seen: HashSet<Vec<usize>> = HashSet::new();
+    let mut result = Vec::new();
+    for house in sudoku.get_houses() {
+        let empty: Vec<usize> = house
+            .get_cells()
+            .iter()
+            .copied()
+            .filter(|i| sudoku.get_cell(*i).unwrap().is_empty())
+            .collect();
+        for size in 1..=empty.len() {
+            for cells in combinations(&empty, size) {
+                let mut digits = DigitHashSet::new();
+                for cell in &cells {
+                    digits.extend(candidates(sudoku, *cell).iter().copied());
+                }
+                if digits.len() == size + 1 && seen.insert(cells.clone()) {
+                    result.push(Als::new(sudoku, cells, digits));
+                }
+            }
+        }
+    }
+    result.sort_by(|a, b| (a.cells.len(), &a.cells).cmp(&(b.cells.len(), &b.cells)));
+    result
+}
+
+fn sorted_digits(digits: &DigitHashSet) -> Vec<Digit> {
+    let mut sorted: Vec<Digit> = digits.iter().copied().collect();
+    sorted.sort_by_key(|d| d.to_usize());
+    sorted
+}
+
+fn common_digits(first: &Als, second: &Als) -> Vec<Digit> {
+    sorted_digits(&first.digits.intersection(&second.digits).copied().collect())
+}
+
+// a digit both sets hold where every cell with the digit in one set sees every such cell in the
+// other, so at most one of the sets can contain it
+fn is_restricted_common(first: &Als, second: &Als, digit: Digit) -> bool {
+    first.digits.contains(&digit)
+        && second.digits.contains(&digit)
+        && second
+            .cells_with(digit)
+            .iter()
+            .all(|c| first.seen_with(digit).contains(c))
+}
+
+fn restricted_commons(first: &Als, second: &Als) -> Vec<Digit> {
+    common_digits(first, second)
+        .into_iter()
+        .filter(|d| is_restricted_common(first, second, *d))
+        .collect()
+}
+
+// removes digit from every cell that sees all the cells of the sets holding it
+fn eliminate_seen(sudoku: &Sudoku, sets: &[&Als], digit: Digit, result: &mut Vec<(usize, Digit)>) {
+    for target in sets[0].seen_with(digit) {
+        if sets[1..]
+            .iter()
+            .all(|s| s.seen_with(digit).contains(target))
+            && !sets.iter().any(|s| s.cells.contains(target))
+            && candidates(sudoku, *target).contains(&digit)
+            && !result.contains(&(*target, digit))
+        {
+            result.push((*target, digit));
+        }
+    }
+}
+
+fn sorted(mut eliminations: Vec<(usize, Digit)>) -> Vec<(usize, Digit)> {
+    eliminations.sort_by_key(|(i, d)| (*i, d.to_usize()));
+    eliminations
+}
+
+// two sets with a restricted common digit x: one of them is a locked set, so any other digit z they
+// share is in one of them; with two restricted commons both sets are locked
+pub fn find_als_xz(sudoku: &Sudoku) -> Option<Step> {
+    let sets = find_all_als(sudoku);
+    for (position, first) in sets.iter().enumerate() {
+        for second in &sets[position + 1..] {
+            if first.overlaps(second) {
+                continue;
+            }
+            let restricted = restricted_commons(first, second);
+            if restricted.is_empty() {
+                continue;
+            }
+            let mut eliminations = Vec::new();
+            for digit in common_digits(first, second) {
+                if restricted.len() == 2 || !restricted.contains(&digit) {
+                    eliminate_seen(sudoku, &[first, second], digit, &mut eliminations);
+                }
+            }
+            if restricted.len() == 2 {
+                for set in [first, second] {
+                    for digit in sorted_digits(&set.digits) {
+                        if !restricted.contains(&digit) {
+                            eliminate_seen(sudoku, &[set], digit, &mut eliminations);
+                        }
+                    }
+                }
+            }
+            if eliminations.is_empty() {
+                continue;
+            }
+            let restricted_names: Vec<String> = restricted
+                .iter()
+                .map(|d| d.to_usize().to_string())
+                .collect();
+            return Some(Step::new(
+                Technique::AlsXz,
+                Vec::new(),
+                sorted(eliminations),
+                format!(
+                    "A={}, B={}, X={}",
+                    first.format(sudoku),
+                    second.format(sudoku),
+                    restricted_names.join(",")
+                ),
+            ));
+        }
+    }
+    None
+}
+
+// sets a and b both have a restricted common with c, on different digits x and y, so a or b is a
+// locked set and a digit z they share is in one of them
+pub fn find_als_xy_wing(sudoku: &Sudoku) -> Option<Step> {
+    let sets = find_all_als(sudoku);
+    for pivot in &sets {
+        let linked: Vec<(&Als, Digit)> = sets
+            .iter()
+            .filter(|s| !s.overlaps(pivot))
+            .flat_map(|s| {
+                restricted_commons(s, pivot)
+                    .into_iter()
+                    .map(move |d| (s, d))
+            })
+            .collect();
+        for (position, (first, x)) in linked.iter().enumerate() {
+            for (second, y) in &linked[position + 1..] {
+                if x == y || first.overlaps(second) {
+                    continue;
+                }
+                let mut eliminations = Vec::new();
+                for z in common_digits(first, second) {
+                    if z != *x && z != *y {
+                        eliminate_seen(sudoku, &[*first, *second], z, &mut eliminations);
+                    }
+                }
+                if eliminations.is_empty() {
+                    continue;
+                }
+                return Some(Step::new(
+                    Technique::AlsXyWing,
+                    Vec::new(),
+                    sorted(eliminations),
+                    format!(
+                        "A={}, B={}, C={}, X={}, Y={}",
+                        first.format(sudoku),
+                        second.format(sudoku),
+                        pivot.format(sudoku),
+                        x.to_usize(),
+                        y.to_usize()
+                    ),
+                ));
+            }
+        }
+    }
+    None
+}
+
+// the petals chosen for each stem candidate and the eliminations they give
+type Blossom<'a> = (Vec<&'a Als>, Vec<(usize, Digit)>);
+
+fn choose_petals<'a>(
+    sudoku: &Sudoku,
+    options: &[Vec<&'a Als>],
+    chosen: &mut Vec<&'a Als>,
+    common: DigitHashSet,
+) -> Option<Blossom<'a>> {
+    if chosen.len() == options.len() {
+        let mut eliminations = Vec::new();
+        for digit in sorted_digits(&common) {
+            eliminate_seen(sudoku, chosen, digit, &mut eliminations);
+        }
+        if eliminations.is_empty() {
+            return None;
+        }
+        return Some((chosen.clone(), eliminations));
+    }
+    for petal in &options[chosen.len()] {
+        if chosen.iter().any(|c| c.overlaps(petal)) {
+            continue;
+        }
+        let remaining: DigitHashSet = common.intersection(&petal.digits).copied().collect();
+        if remaining.is_empty() {
+            continue;
+        }
+        chosen.push(petal);
+        let found = choose_petals(sudoku, options, chosen, remaining);
+        chosen.pop();
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+// a stem cell with an almost locked set for each of its candidates, whatever the stem holds one
+// of the sets becomes locked, so a digit common to all sets is in one of them
+pub fn find_death_blossom(sudoku: &Sudoku) -> Option<Step> {
+    let sets = find_all_als(sudoku);
+    for stem in sudoku.cells().filter(|c| c.is_empty()) {
+        let stem_index = *stem.get_index();
+        let stem_digits = sorted_digits(stem.get_candidates());
+        if stem_digits.len() < 2 || stem_digits.len() > 3 {
+            continue;
+        }
+        let options: Vec<Vec<&Als>> = stem_digits
+            .iter()
+            .map(|digit| {
+                sets.iter()
+                    .filter(|s| !s.cells.contains(&stem_index) && s.digits.contains(digit))
+                    .filter(|s| {
+                        s.cells_with(*digit)
+                            .iter()
+                            .all(|c| sudoku.sees(*c, stem_index))
+                    })
+                    .collect()
+            })
+            .collect();
+        if options.iter().any(|o| o.is_empty()) {
+            continue;
+        }
+        let common: DigitHashSet = sudoku
+            .get_digits()
+            .into_iter()
+            .filter(|d| !stem.has_candidate(*d))
+            .collect();
+        let mut chosen = Vec::new();
+        if let Some((petals, eliminations)) = choose_petals(sudoku, &options, &mut chosen, common) {
+            let names: Vec<String> = stem_digits
+                .iter()
+                .zip(petals.iter())
+                .map(|(d, p)| format!("{}: {}", d.to_usize(), p.format(sudoku)))
+                .collect();
+            return Some(Step::new(
+                Technique::DeathBlossom,
+                Vec::new(),
+                sorted(eliminations),
+                format!("stem {}, {}", stem.get_name(), names.join(", ")),
+            ));
+        }
+    }
+    None
+}
+
+fn union_of(sudoku: &Sudoku, cells: &[usize]) -> DigitHashSet {
+    let mut digits = DigitHashSet::new();
+    for cell in cells {
+        digits.extend(candidates(sudoku, *cell).iter().copied());
+    }
+    digits
+}
+
+fn empty_cells(sudoku: &Sudoku, cells: &[usize]) -> Vec<usize> {
+    cells
+        .iter()
+        .copied()
+        .filter(|i| sudoku.get_cell(*i).unwrap().is_empty())
+        .collect()
+}
+
+// the subsets of cells up to largest cells with the candidates they hold
+fn subsets(sudoku: &Sudoku, cells: &[usize], largest: usize) -> Vec<(Vec<usize>, DigitHashSet)> {
+    (1..=largest.min(cells.len()))
+        .flat_map(|size| combinations(cells, size))
+        .map(|part| {
+            let digits = union_of(sudoku, &part);
+            (part, digits)
+        })
+        .collect()
+}
+
+// sue de coq: cells in the intersection of a section and a line with at least two more candidates
+// than cells, completed by cells from the rest of the line and the rest of the section that share
+// no digits; together they hold exactly as many digits as cells, each digit at most once
+pub fn find_sue_de_coq(sudoku: &Sudoku) -> Option<Step> {
+    for (section, line) in section_line_pairs(sudoku) {
+        let section_cells = section.get_cells();
+        let line = line.get_cells();
+        let shared: Vec<usize> = empty_cells(sudoku, line)
+            .into_iter()
+            .filter(|i| section_cells.contains(i))
+            .collect();
+        if shared.len() < 2 {
+            continue;
+        }
+        let line_rest: Vec<usize> = empty_cells(sudoku, line)
+            .into_iter()
+            .filter(|i| !section_cells.contains(i))
+            .collect();
+        let section_rest: Vec<usize> = empty_cells(sudoku, section_cells)
+            .into_iter()
+            .filter(|i| !line.contains(i))
+            .collect();
+        let line_parts = subsets(sudoku, &line_rest, 3);
+        let section_parts = subsets(sudoku, &section_rest, 3);
+        for core in (2..=shared.len()).flat_map(|size| combinations(&shared, size)) {
+            let core_digits = union_of(sudoku, &core);
+            if core_digits.len() < core.len() + 2 {
+                continue;
+            }
+            for (line_cells, line_digits) in &line_parts {
+                if line_digits.is_disjoint(&core_digits) {
+                    continue;
+                }
+                for (section_part, section_digits) in &section_parts {
+                    if section_digits.is_disjoint(&core_digits)
+                        || !line_digits.is_disjoint(section_digits)
+                    {
+                        continue;
+                    }
+                    let mut all = core_digits.clone();
+                    all.extend(line_digits.iter().copied());
+                    all.extend(section_digits.iter().copied());
+                    if all.len() != core.len() + line_cells.len() + section_part.len() {
+                        continue;
+                    }
+                    let mut eliminations = Vec::new();
+                    for digit in sorted_digits(&all) {
+                        let in_line = !section_digits.contains(&digit);
+                        let in_section = !line_digits.contains(&digit);
+                        for cell in &line_rest {
+                            if in_line
+                                && !line_cells.contains(cell)
+                                && candidates(sudoku, *cell).contains(&digit)
+                            {
+                                eliminations.push((*cell, digit));
+                            }
+                        }
+                        for cell in &section_rest {
+                            if in_section
+                                && !section_part.contains(cell)
+                                && candidates(sudoku, *cell).contains(&digit)
+                            {
+                                eliminations.push((*cell, digit));
+                            }
+                        }
+                    }
+                    if eliminations.is_empty() {
+                        continue;
+                    }
+                    return Some(Step::new(
+                        Technique::SueDeCoq,
+                        Vec::new(),
+                        sorted(eliminations),
+                        format!(
+                            "{} {{{}}} with {} {{{}}} and {} {{{}}}",
+                            format_cells(sudoku, &core),
+                            format_digits(&core_digits),
+                            format_cells(sudoku, line_cells),
+                            format_digits(line_digits),
+                            format_cells(sudoku, section_part),
+                            format_digits(section_digits)
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn find_step(sudoku: &Sudoku) -> Option<Step> {
+    let finders: [fn(&Sudoku) -> Option<Step>; 4] = [
+        find_sue_de_coq,
+        find_als_xz,
+        find_als_xy_wing,
+        find_death_blossom,
+    ];
+    finders.iter().find_map(|finder| finder(sudoku))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::DigitSet;
+    use Digit::*;
+
+    fn grid_with_blanks(layout: &[(usize, &[Digit])]) -> Sudoku {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.distribute_set_to_make_correct_sudoku(DigitSet::new_full());
+        for (index, digits) in layout.iter() {
+            let cell = sudoku.get_mut_cell(*index).unwrap();
+            cell.set_value(None);
+            cell.set_candidates(digits.iter().copied().collect::<DigitHashSet>());
+        }
+        sudoku
+    }
+
+    fn blossom_grid() -> Sudoku {
+        grid_with_blanks(&[
+            (0, &[Three, Seven]),
+            (4, &[Two, Three]),
+            (36, &[One, Three]),
+            (40, &[One, Two]),
+        ])
+    }
+
+    #[test]
+    fn test_find_all_als() {
+        let sudoku = blossom_grid();
+        let sets = find_all_als(&sudoku);
+        // four bivalue cells and the pairs in rows one and five and columns one and five
+        assert_eq!(sets.len(), 8);
+        assert_eq!(sets[0].get_cells(), &vec![0]);
+        assert_eq!(sets[4].get_cells(), &vec![0, 4]);
+    }
+    #[test]
+    fn test_als_xz() {
+        let sudoku = grid_with_blanks(&[
+            (0, &[One, Two]),
+            (4, &[One, Three]),
+            (13, &[Two, Three]),
+            (10, &[Two, Four]),
+        ]);
+        let step = find_als_xz(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(10, Two)]);
+        assert_eq!(
+            step.get_description(),
+            "A=r1c1 {12}, B=r1c5,r2c5 {123}, X=1"
+        );
+    }
+    #[test]
+    fn test_als_xy_wing() {
+        let sudoku = blossom_grid();
+        let step = find_als_xy_wing(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(0, Three)]);
+    }
+    #[test]
+    fn test_death_blossom() {
+        let sudoku = blossom_grid();
+        let step = find_death_blossom(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(0, Three)]);
+        assert!(step.get_description().starts_with("stem r5c5"));
+    }
+    #[test]
+    fn test_sue_de_coq() {
+        let sudoku = grid_with_blanks(&[
+            (0, &[One, Two, Three, Four]),
+            (1, &[One, Two, Three, Four]),
+            (4, &[One, Two]),
+            (9, &[Three, Four]),
+            (6, &[One, Five]),
+            (20, &[Three, Six]),
+        ]);
+        let step = find_sue_de_coq(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(6, One), (20, Three)]);
+    }
+    #[test]
+    fn test_als_steps_are_sound_on_a_puzzle() {
+        let sudoku = Sudoku::from_line(
+            "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+        )
+        .unwrap();
+        let solution = sudoku.get_solution().unwrap();
+        let finders: [fn(&Sudoku) -> Option<Step>; 4] = [
+            find_sue_de_coq,
+            find_als_xz,
+            find_als_xy_wing,
+            find_death_blossom,
+        ];
+        for finder in finders.iter() {
+            if let Some(step) = finder(&sudoku) {
+                for (index, digit) in step.get_eliminations() {
+                    let value = solution.get_cell(*index).unwrap().get_value();
+                    assert_ne!(value, &Some(*digit), "{}", step);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+// the techniques most puzzles are solved with: singles, locked candidates and subsets
+use super::{candidates, cells_with_candidate, combinations, format_cells, Step, Technique};
+use crate::datastructures::constraint::Constraint;
+use crate::datastructures::digit::{Digit, DigitHashSet};
+use crate::datastructures::sudoku::Sudoku;
+
+fn empty_cells(sudoku: &Sudoku, house: &[usize]) -> Vec<usize> {
+    house
+        .iter()
+        .copied()
+        .filter(|i| sudoku.get_cell(*i).unwrap().is_empty())
+        .collect()
+}
+
+// the last empty cell of a house gets the one digit that is missing
+pub fn find_full_house(sudoku: &Sudoku) -> Option<Step> {
+    for house in sudoku.get_houses() {
+        let empty = empty_cells(sudoku, house.get_cells());
+        if empty.len() != 1 {
+            continue;
+        }
+        if let Some(digit) = candidates(sudoku, empty[0]).iter().next() {
+            return Some(Step::new(
+                Technique::FullHouse,
+                vec![(empty[0], *digit)],
+                Vec::new(),
+                house.get_name(),
+            ));
+        }
+    }
+    None
+}
+
+pub fn find_naked_single(sudoku: &Sudoku) -> Option<Step> {
+    for cell in sudoku.cells().filter(|c| c.is_empty()) {
+        if cell.get_candidates().len() != 1 {
+            continue;
+        }
+        let digit = *cell.get_candidates().iter().next().unwrap();
+        return Some(Step::new(
+            Technique::NakedSingle,
+            vec![(*cell.get_index(), digit)],
+            Vec::new(),
+            cell.get_name(),
+        ));
+    }
+    None
+}
+
+pub fn find_hidden_single(sudoku: &Sudoku) -> Option<Step> {
+    for house in sudoku.get_houses() {
+        for digit in sudoku.get_digits() {
+            let positions = cells_with_candidate(sudoku, house.get_cells(), digit);
+            if positions.len() == 1 {
+                return Some(Step::new(
+                    Technique::HiddenSingle,
+                    vec![(positions[0], digit)],
+                    Vec::new(),
+                    format!("{} in {}", digit.to_usize(), house.get_name()),
+                ));
+            }
+        }
+    }
+    None
+}
+
+// a digit confined to one line inside a section (pointing) or to one section inside a line
+// (claiming) can be removed from the rest of the other house
+fn find_locked_candidates(sudoku: &Sudoku, pointing: bool) -> Option<Step> {
+    let houses = sudoku.get_houses();
+    let sources = houses.iter().filter(|house| house.is_line() != pointing);
+    for source in sources {
+        for digit in sudoku.get_digits() {
+            let positions = cells_with_candidate(sudoku, source.get_cells(), digit);
+            if positions.len() < 2 {
+                continue;
+            }
+            for target in houses.iter().filter(|house| *house != source) {
+                if !positions.iter().all(|p| target.contains(*p)) {
+                    continue;
+                }
+                let eliminations: Vec<(usize, Digit)> =
+                    cells_with_candidate(sudoku, target.get_cells(), digit)
+                        .into_iter()
+                        .filter(|i| !source.contains(*i))
+                        .map(|i| (i, digit))
+                        .collect();
+                if eliminations.is_empty() {
+                    continue;
+                }
+                let technique = if pointing {
+                    Technique::LockedCandidatesPointing
+                } else {
+                    Technique::LockedCandidatesClaiming
+                };
+                return Some(Step::new(
+                    technique,
+                    Vec::new(),
+                    eliminations,
+                    format!(
+                        "{} in {} ({})",
+                        digit.to_usize(),
+                        source.get_name(),
+                        format_cells(sudoku, &positions)
+                    ),
+                ));
+            }
+        }
+    }
+    None
+}
+
+pub fn find_locked_candidates_pointing(sudoku: &Sudoku) -> Option<Step> {
+    find_locked_candidates(sudoku, true)
+}
+
+pub fn find_locked_candidates_claiming(sudoku: &Sudoku) -> Option<Step> {
+    find_locked_candidates(sudoku, false)
+}
+
+fn naked_technique(size: usize) -> Technique {
+    match size {
+        2 => Technique::NakedPair,
+        3 => Technique::NakedTriple,
+        _ => Technique::NakedQuad,
+    }
+}
+
+fn hidden_technique(size: usize) -> Technique {
+    match size {
+        2 => Technique::HiddenPair,
+        3 => Technique::HiddenTriple,
+        _ => Technique::HiddenQuad,
+    }
+}
+
+fn sorted_digits(digits: &DigitHashSet) -> Vec<Digit> {
+    let mut sorted: Vec<Digit> = digits.iter().copied().collect();
+    sorted.sort_by_key(|d| d.to_usize());
+    sorted
+}
+
+// size cells of a house that hold only size digits between them, the digits can be removed from
+// the rest of the house
+pub fn find_naked_subset(sudoku: &Sudoku, size: usize) -> Option<Step> {
+    for house in sudoku.get_houses() {
+        let empty = empty_cells(sudoku, house.get_cells());
+        let options: Vec<usize> = empty
+            .iter()
+            .copied()
+            .filter(|i| (2..=size).contains(&candidates(sudoku, *i).len()))
+            .collect();
+        for cells in combinations(&options, size) {
+            let mut digits = DigitHashSet::new();
+            for cell in &cells {
+                digits.extend(candidates(sudoku, *cell).iter().copied());
+            }
+            if digits.len() != size {
+                continue;
+            }
+            let mut eliminations = Vec::new();
+            for other in empty.iter().filter(|i| !cells.contains(i)) {
+                for digit in sorted_digits(&digits) {
+                    if candidates(sudoku, *other).contains(&digit) {
+                        eliminations.push((*other, digit));
+                    }
+                }
+            }
+            if eliminations.is_empty() {
+                continue;
+            }
+            return Some(Step::new(
+                naked_technique(size),
+                Vec::new(),
+                eliminations,
+                format!("{} in {}", format_cells(sudoku, &cells), house.get_name()),
+            ));
+        }
+    }
+    None
+}
+
+// size digits of a house that fit in only size cells, the other candidates of those cells can be
+// removed
+pub fn find_hidden_subset(sudoku: &Sudoku, size: usize) -> Option<Step> {
+    for house in sudoku.get_houses() {
+        let options: Vec<Digit> = sudoku
+            .get_digits()
+            .into_iter()
+            .filter(|d| {
+                (1..=size).contains(&cells_with_candidate(sudoku, house.get_cells(), *d).len())
+            })
+            .collect();
+        for digits in combinations(&options, size) {
+            let mut cells: Vec<usize> = Vec::new();
+            for digit in &digits {
+                cells.extend(cells_with_candidate(sudoku, house.get_cells(), *digit));
+            }
+            cells.sort_unstable();
+            cells.dedup();
+            if cells.len() != size {
+                continue;
+            }
+            let mut eliminations = Vec::new();
+            for cell in &cells {
+                for digit in sorted_digits(candidates(sudoku, *cell)) {
+                    if !digits.contains(&digit) {
+                        eliminations.push((*cell, digit));
+                    }
+                }
+            }
+            if eliminations.is_empty() {
+                continue;
+            }
+            let names: String = digits.iter().map(|d| d.to_usize().to_string()).collect();
+            return Some(Step::new(
+                hidden_technique(size),
+                Vec::new(),
+                eliminations,
+                format!(
+                    "{} in {} ({})",
+                    names,
+                    house.get_name(),
+                    format_cells(sudoku, &cells)
+                ),
+            ));
+        }
+    }
+    None
+}
+
+pub fn find_step(sudoku: &Sudoku) -> Option<Step> {
+    let finders: [fn(&Sudoku) -> Option<Step>; 5] = [
+        find_full_house,
+        find_naked_single,
+        find_hidden_single,
+        find_locked_candidates_pointing,
+        find_locked_candidates_claiming,
+    ];
+    if let Some(step) = finders.iter().find_map(|finder| finder(sudoku)) {
+        return Some(step);
+    }
+    (2..=4).find_map(|size| {
+        find_naked_subset(sudoku, size).or_else(|| find_hidden_subset(sudoku, size))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Digit::*;
+
+    #[test]
+    fn test_singles() {
+        let mut sudoku = Sudoku::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        // r5c5 is the only empty cell left in its section once the rest is filled in
+        for (index, digit) in [(30, Seven), (32, One), (48, Nine), (50, Four)] {
+            sudoku.set_digit(index, digit);
+        }
+        let step = find_full_house(&sudoku).unwrap();
+        assert_eq!(step.get_placements(), &vec![(40, Five)]);
+        assert_eq!(step.get_description(), "section 5");
+        let step = find_naked_single(&sudoku).unwrap();
+        assert_eq!(step.get_technique(), &Technique::NakedSingle);
+        assert!(find_hidden_single(&sudoku).is_some());
+    }
+    #[test]
+    fn test_locked_candidates_pointing() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        // one only fits in the first row of section 1
+        for index in [9, 10, 11, 18, 19, 20] {
+            sudoku.remove_candidate(index, One);
+        }
+        let step = find_locked_candidates_pointing(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations().len(), 6);
+        assert_eq!(step.get_eliminations()[0], (3, One));
+        assert_eq!(step.get_description(), "1 in section 1 (r1c1,r1c2,r1c3)");
+        assert!(find_locked_candidates_claiming(&sudoku).is_none());
+    }
+    #[test]
+    fn test_naked_and_hidden_pairs() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let pair: DigitHashSet = [One, Two].iter().copied().collect();
+        for index in [0, 1] {
+            sudoku
+                .get_mut_cell(index)
+                .unwrap()
+                .set_candidates(pair.clone());
+        }
+        let step = find_naked_subset(&sudoku, 2).unwrap();
+        assert_eq!(step.get_technique(), &Technique::NakedPair);
+        assert_eq!(step.get_eliminations()[0], (2, One));
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        for index in 2..9 {
+            sudoku.remove_candidate(index, One);
+            sudoku.remove_candidate(index, Two);
+        }
+        let step = find_hidden_subset(&sudoku, 2).unwrap();
+        assert_eq!(step.get_technique(), &Technique::HiddenPair);
+        assert_eq!(step.get_eliminations().len(), 14);
+    }
+}
@@ -0,0 +1,640 @@
+// chains over candidate nodes, a node is a digit in one cell or, for grouped nodes, a digit in two
+// or three cells of a section that also share a row or column
+// a strong link means the nodes can not both be false, a weak link that they can not both be true
+use super::links::LinkKind;
+use super::{candidates, cells_with_candidate, format_digits, section_line_pairs, Step, Technique};
+use crate::datastructures::digit::Digit;
+use crate::datastructures::sudoku::Sudoku;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    cells: Vec<usize>,
+    digit: Digit,
+}
+
+impl Node {
+    pub fn new(mut cells: Vec<usize>, digit: Digit) -> Self {
+        cells.sort_unstable();
+        Node { cells, digit }
+    }
+    pub fn get_cells(&self) -> &Vec<usize> {
+        &self.cells
+    }
+    pub fn get_digit(&self) -> &Digit {
+        &self.digit
+    }
+    pub fn is_grouped(&self) -> bool {
+        self.cells.len() > 1
+    }
+    // the cells in eureka notation, grouped cells share their row or column like r1c23
+    pub fn format_cells(&self, sudoku: &Sudoku) -> String {
+        let cells: Vec<_> = self
+            .cells
+            .iter()
+            .map(|i| sudoku.get_cell(*i).unwrap())
+            .collect();
+        let rows: Vec<String> = cells
+            .iter()
+            .map(|c| (c.get_row() + 1).to_string())
+            .collect();
+        let columns: Vec<String> = cells
+            .iter()
+            .map(|c| (c.get_column() + 1).to_string())
+            .collect();
+        let join = |parts: Vec<String>| {
+            let mut unique = parts;
+            unique.dedup();
+            unique.concat()
+        };
+        format!("r{}c{}", join(rows), join(columns))
+    }
+}
+
+pub struct ChainGraph {
+    nodes: Vec<Node>,
+    strong: Vec<Vec<usize>>,
+    // every node linked to a node, strong links included as they also work as weak links
+    weak: Vec<Vec<usize>>,
+}
+
+impl ChainGraph {
+    pub fn new(sudoku: &Sudoku, grouped: bool) -> Self {
+        let mut nodes: Vec<Node> = Vec::new();
+        for cell in sudoku.cells().filter(|c| c.is_empty()) {
+            let mut digits: Vec<Digit> = cell.get_candidates().iter().copied().collect();
+            digits.sort_by_key(|d| d.to_usize());
+            for digit in digits {
+                nodes.push(Node::new(vec![*cell.get_index()], digit));
+            }
+        }
+        if grouped {
+            nodes.extend(grouped_nodes(sudoku));
+        }
+        let mut graph = ChainGraph {
+            strong: vec![Vec::new(); nodes.len()],
+            weak: vec![Vec::new(); nodes.len()],
+            nodes,
+        };
+        graph.add_cell_links(sudoku);
+        graph.add_house_links(sudoku);
+        graph
+    }
+
+    fn add_link(&mut self, first: usize, second: usize, kind: LinkKind) {
+        if kind == LinkKind::Strong && !self.strong[first].contains(&second) {
+            self.strong[first].push(second);
+            self.strong[second].push(first);
+        }
+        if !self.weak[first].contains(&second) {
+            self.weak[first].push(second);
+            self.weak[second].push(first);
+        }
+    }
+
+    fn add_cell_links(&mut self, sudoku: &Sudoku) {
+        let mut by_cell: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (position, node) in self.nodes.iter().enumerate() {
+            if !node.is_grouped() {
+                by_cell.entry(node.cells[0]).or_default().push(position);
+            }
+        }
+        for cell in sudoku.cells().filter(|c| c.is_empty()) {
+            let in_cell = by_cell.remove(cell.get_index()).unwrap_or_default();
+            let kind = if in_cell.len() == 2 {
+                LinkKind::Strong
+            } else {
+                LinkKind::Weak
+            };
+            for (position, first) in in_cell.iter().enumerate() {
+                for second in &in_cell[position + 1..] {
+                    self.add_link(*first, *second, kind);
+                }
+            }
+        }
+    }
+
+    fn add_house_links(&mut self, sudoku: &Sudoku) {
+        for house in sudoku.get_houses() {
+            for digit in sudoku.get_digits() {
+                let positions = cells_with_candidate(sudoku, house.get_cells(), digit);
+                if positions.len() < 2 {
+                    continue;
+                }
+                let inside: Vec<usize> = (0..self.nodes.len())
+                    .filter(|n| {
+                        let node = &self.nodes[*n];
+                        node.digit == digit && node.cells.iter().all(|c| house.contains(*c))
+                    })
+                    .collect();
+                for (position, first) in inside.iter().enumerate() {
+                    for second in &inside[position + 1..] {
+                        let a = &self.nodes[*first].cells;
+                        let b = &self.nodes[*second].cells;
+                        if a.iter().any(|c| b.contains(c)) {
+                            continue;
+                        }
+                        let kind = if a.len() + b.len() == positions.len() {
+                            LinkKind::Strong
+                        } else {
+                            LinkKind::Weak
+                        };
+                        self.add_link(*first, *second, kind);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_nodes(&self) -> &Vec<Node> {
+        &self.nodes
+    }
+
+    pub fn find_node(&self, cells: &[usize], digit: Digit) -> Option<usize> {
+        let node = Node::new(cells.to_vec(), digit);
+        self.nodes.iter().position(|n| *n == node)
+    }
+
+    // everything that follows from giving node the value, as a map from each reached
+    // (node, value) to the one it was reached from
+    pub fn implications(&self, node: usize, value: bool) -> Implications {
+        let mut parent: HashMap<(usize, bool), (usize, bool)> = HashMap::new();
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+        parent.insert((node, value), (node, value));
+        queue.push_back((node, value));
+        while let Some((current, is_true)) = queue.pop_front() {
+            order.push((current, is_true));
+            let next_nodes = if is_true {
+                &self.weak[current]
+            } else {
+                &self.strong[current]
+            };
+            for next in next_nodes {
+                let state = (*next, !is_true);
+                if let Entry::Vacant(entry) = parent.entry(state) {
+                    entry.insert((current, is_true));
+                    queue.push_back(state);
+                }
+            }
+        }
+        Implications {
+            start: (node, value),
+            parent,
+            order,
+        }
+    }
+
+    // the chain from the start of implications to state in eureka notation
+    pub fn format_chain(
+        &self,
+        sudoku: &Sudoku,
+        implications: &Implications,
+        state: (usize, bool),
+    ) -> String {
+        let path = implications.path_to(state);
+        let mut result = String::new();
+        let mut position = 0;
+        while position < path.len() {
+            // consecutive nodes in the same cells are written together, like (1=2)r1c1
+            let cells = &self.nodes[path[position].0].cells;
+            let mut end = position;
+            let mut digits = self.nodes[path[position].0].digit.to_usize().to_string();
+            while end + 1 < path.len() && &self.nodes[path[end + 1].0].cells == cells {
+                digits.push(link_symbol(path[end + 1].1));
+                digits.push_str(&self.nodes[path[end + 1].0].digit.to_usize().to_string());
+                end += 1;
+            }
+            if position > 0 {
+                result.push(link_symbol(path[position].1));
+            }
+            result.push_str(&format!(
+                "({}){}",
+                digits,
+                self.nodes[path[position].0].format_cells(sudoku)
+            ));
+            position = end + 1;
+        }
+        result
+    }
+}
+
+// a node that became true was reached through a strong link, a false one through a weak link
+fn link_symbol(value: bool) -> char {
+    if value {
+        '='
+    } else {
+        '-'
+    }
+}
+
+pub struct Implications {
+    start: (usize, bool),
+    parent: HashMap<(usize, bool), (usize, bool)>,
+    order: Vec<(usize, bool)>,
+}
+
+impl Implications {
+    pub fn contains(&self, state: (usize, bool)) -> bool {
+        self.parent.contains_key(&state)
+    }
+    // reached states in the order they were found, the start first
+    pub fn get_order(&self) -> &Vec<(usize, bool)> {
+        &self.order
+    }
+    pub fn path_to(&self, state: (usize, bool)) -> Vec<(usize, bool)> {
+        let mut path = vec![state];
+        let mut current = state;
+        while current != self.start {
+            current = self.parent[&current];
+            path.push(current);
+        }
+        path.reverse();
+        path
+    }
+}
+
+fn grouped_nodes(sudoku: &Sudoku) -> Vec<Node> {
+    let mut result = Vec::new();
+    for (section, line) in section_line_pairs(sudoku) {
+        let shared: Vec<usize> = line
+            .get_cells()
+            .iter()
+            .copied()
+            .filter(|i| section.contains(*i))
+            .collect();
+        for digit in sudoku.get_digits() {
+            let cells = cells_with_candidate(sudoku, &shared, digit);
+            if cells.len() >= 2 {
+                result.push(Node::new(cells, digit));
+            }
+        }
+    }
+    result
+}
+
+fn node_name(sudoku: &Sudoku, node: &Node) -> String {
+    format!("({}){}", node.digit.to_usize(), node.format_cells(sudoku))
+}
+
+// candidates that can not be true when at least one of the two nodes is
+fn eliminations_for_either(sudoku: &Sudoku, first: &Node, second: &Node) -> Vec<(usize, Digit)> {
+    let mut result = Vec::new();
+    if first.digit == second.digit {
+        let mut seen: Vec<usize> = first.cells.clone();
+        seen.extend(second.cells.iter());
+        for cell in sudoku.cells().filter(|c| c.is_empty()) {
+            let index = *cell.get_index();
+            if cell.has_candidate(first.digit)
+                && !seen.contains(&index)
+                && seen.iter().all(|s| sudoku.sees(index, *s))
+            {
+                result.push((index, first.digit));
+            }
+        }
+    } else if !first.is_grouped() && !second.is_grouped() {
+        let (a, b) = (first.cells[0], second.cells[0]);
+        if a == b {
+            let mut others: Vec<Digit> = candidates(sudoku, a)
+                .iter()
+                .copied()
+                .filter(|d| *d != first.digit && *d != second.digit)
+                .collect();
+            others.sort_by_key(|d| d.to_usize());
+            result.extend(others.into_iter().map(|d| (a, d)));
+        } else if sudoku.sees(a, b) {
+            if candidates(sudoku, b).contains(&first.digit) {
+                result.push((b, first.digit));
+            }
+            if candidates(sudoku, a).contains(&second.digit) {
+                result.push((a, second.digit));
+            }
+        }
+    }
+    result
+}
+
+// alternating inference chain: from a node being false the chain reaches another node being
+// true, so one of the two ends is true
+pub fn find_aic(sudoku: &Sudoku) -> Option<Step> {
+    let graph = ChainGraph::new(sudoku, true);
+    for start in 0..graph.nodes.len() {
+        let implications = graph.implications(start, false);
+        for (end, value) in implications.get_order() {
+            if !*value || *end == start {
+                continue;
+            }
+            let eliminations =
+                eliminations_for_either(sudoku, &graph.nodes[start], &graph.nodes[*end]);
+            if eliminations.is_empty() {
+                continue;
+            }
+            let path = implications.path_to((*end, true));
+            let technique = if path.iter().any(|(n, _)| graph.nodes[*n].is_grouped()) {
+                Technique::GroupedAic
+            } else {
+                Technique::Aic
+            };
+            return Some(Step::new(
+                technique,
+                Vec::new(),
+                eliminations,
+                graph.format_chain(sudoku, &implications, (*end, true)),
+            ));
+        }
+    }
+    None
+}
+
+// an alternating chain whose ends are weakly linked closes into a loop in which every weak link
+// acts as a strong one
+pub fn find_continuous_nice_loop(sudoku: &Sudoku) -> Option<Step> {
+    let graph = ChainGraph::new(sudoku, true);
+    for start in 0..graph.nodes.len() {
+        let implications = graph.implications(start, false);
+        for (end, value) in implications.get_order() {
+            if !*value || *end == start || !graph.weak[*end].contains(&start) {
+                continue;
+            }
+            let mut path = implications.path_to((*end, true));
+            if path.len() < 4 {
+                continue;
+            }
+            path.push((start, false));
+            let mut eliminations: Vec<(usize, Digit)> = Vec::new();
+            for pair in path.windows(2) {
+                // the false to true steps are the strong links, the others become strong
+                if pair[1].1 {
+                    continue;
+                }
+                let (first, second) = (&graph.nodes[pair[0].0], &graph.nodes[pair[1].0]);
+                let loop_cells: HashSet<(usize, Digit)> = path
+                    .iter()
+                    .flat_map(|(n, _)| {
+                        let node = &graph.nodes[*n];
+                        node.cells.iter().map(move |c| (*c, node.digit))
+                    })
+                    .collect();
+                for elimination in eliminations_for_either(sudoku, first, second) {
+                    if !loop_cells.contains(&elimination) && !eliminations.contains(&elimination) {
+                        eliminations.push(elimination);
+                    }
+                }
+            }
+            if eliminations.is_empty() {
+                continue;
+            }
+            eliminations.sort_by_key(|(i, d)| (*i, d.to_usize()));
+            let chain = graph.format_chain(sudoku, &implications, (*end, true));
+            return Some(Step::new(
+                Technique::ContinuousNiceLoop,
+                Vec::new(),
+                eliminations,
+                format!("{}-{}", chain, node_name(sudoku, &graph.nodes[start])),
+            ));
+        }
+    }
+    None
+}
+
+// assuming a value for a node leads to the opposite value, so the assumption was wrong
+pub fn find_discontinuous_nice_loop(sudoku: &Sudoku) -> Option<Step> {
+    let graph = ChainGraph::new(sudoku, true);
+    for start in 0..graph.nodes.len() {
+        let node = &graph.nodes[start];
+        for value in [true, false] {
+            if !value && node.is_grouped() {
+                continue;
+            }
+            let implications = graph.implications(start, value);
+            if !implications.contains((start, !value)) {
+                continue;
+            }
+            let (placements, eliminations) = if value {
+                (
+                    Vec::new(),
+                    node.cells.iter().map(|c| (*c, node.digit)).collect(),
+                )
+            } else {
+                (vec![(node.cells[0], node.digit)], Vec::new())
+            };
+            return Some(Step::new(
+                Technique::DiscontinuousNiceLoop,
+                placements,
+                eliminations,
+                graph.format_chain(sudoku, &implications, (start, !value)),
+            ));
+        }
+    }
+    None
+}
+
+// all of the assumptions lead to the same conclusion about a single candidate
+fn common_conclusion(
+    sudoku: &Sudoku,
+    graph: &ChainGraph,
+    starts: &[usize],
+    technique: Technique,
+    premise: String,
+) -> Option<Step> {
+    let branches: Vec<Implications> = starts
+        .iter()
+        .map(|s| graph.implications(*s, true))
+        .collect();
+    for (node, value) in branches[0].get_order() {
+        let target = &graph.nodes[*node];
+        if target.is_grouped() || starts.contains(node) {
+            continue;
+        }
+        if !branches.iter().all(|b| b.contains((*node, *value))) {
+            continue;
+        }
+        let (placements, eliminations) = if *value {
+            (vec![(target.cells[0], target.digit)], Vec::new())
+        } else {
+            (Vec::new(), vec![(target.cells[0], target.digit)])
+        };
+        let chains: Vec<String> = branches
+            .iter()
+            .map(|b| graph.format_chain(sudoku, b, (*node, *value)))
+            .collect();
+        return Some(Step::new(
+            technique,
+            placements,
+            eliminations,
+            format!("{}: {}", premise, chains.join(", ")),
+        ));
+    }
+    None
+}
+
+pub fn find_cell_forcing_chain(sudoku: &Sudoku) -> Option<Step> {
+    let graph = ChainGraph::new(sudoku, true);
+    for cell in sudoku.cells().filter(|c| c.is_empty()) {
+        let index = *cell.get_index();
+        let starts: Vec<usize> = cell
+            .get_candidates()
+            .iter()
+            .filter_map(|d| graph.find_node(&[index], *d))
+            .collect();
+        if starts.len() < 2 {
+            continue;
+        }
+        let premise = format!(
+            "{} is {}",
+            cell.get_name(),
+            format_digits(cell.get_candidates())
+        );
+        if let Some(step) = common_conclusion(
+            sudoku,
+            &graph,
+            &starts,
+            Technique::CellForcingChain,
+            premise,
+        ) {
+            return Some(step);
+        }
+    }
+    None
+}
+
+pub fn find_region_forcing_chain(sudoku: &Sudoku) -> Option<Step> {
+    let graph = ChainGraph::new(sudoku, true);
+    for house in sudoku.get_houses() {
+        for digit in sudoku.get_digits() {
+            let positions = cells_with_candidate(sudoku, house.get_cells(), digit);
+            if positions.len() < 2 {
+                continue;
+            }
+            let starts: Vec<usize> = positions
+                .iter()
+                .filter_map(|c| graph.find_node(&[*c], digit))
+                .collect();
+            let names: Vec<String> = starts
+                .iter()
+                .map(|s| node_name(sudoku, &graph.nodes[*s]))
+                .collect();
+            let premise = format!("one of {}", names.join(", "));
+            if let Some(step) = common_conclusion(
+                sudoku,
+                &graph,
+                &starts,
+                Technique::RegionForcingChain,
+                premise,
+            ) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+pub fn find_step(sudoku: &Sudoku) -> Option<Step> {
+    let finders: [fn(&Sudoku) -> Option<Step>; 5] = [
+        find_aic,
+        find_continuous_nice_loop,
+        find_discontinuous_nice_loop,
+        find_cell_forcing_chain,
+        find_region_forcing_chain,
+    ];
+    finders.iter().find_map(|finder| finder(sudoku))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::{DigitHashSet, DigitSet};
+    use Digit::*;
+
+    const PUZZLE: &str =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    fn xy_chain_grid() -> Sudoku {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.distribute_set_to_make_correct_sudoku(DigitSet::new_full());
+        let layout: [(usize, &[Digit]); 4] = [
+            (0, &[One, Two]),
+            (4, &[Two, Three]),
+            (40, &[Three, One]),
+            (36, &[One, Four]),
+        ];
+        for (index, digits) in layout.iter() {
+            let cell = sudoku.get_mut_cell(*index).unwrap();
+            cell.set_value(None);
+            cell.set_candidates(digits.iter().copied().collect::<DigitHashSet>());
+        }
+        sudoku
+    }
+
+    // a step is sound if it never removes or contradicts a digit of the solution
+    fn assert_sound(sudoku: &Sudoku, step: &Step) {
+        let solution = sudoku.get_solution().unwrap();
+        for (index, digit) in step.get_eliminations() {
+            assert_ne!(
+                solution.get_cell(*index).unwrap().get_value(),
+                &Some(*digit),
+                "{}",
+                step
+            );
+        }
+        for (index, digit) in step.get_placements() {
+            assert_eq!(
+                solution.get_cell(*index).unwrap().get_value(),
+                &Some(*digit),
+                "{}",
+                step
+            );
+        }
+    }
+
+    #[test]
+    fn test_node_notation() {
+        let sudoku = Sudoku::new(9, 9, 3, 3);
+        assert_eq!(Node::new(vec![1, 2], One).format_cells(&sudoku), "r1c23");
+        assert_eq!(Node::new(vec![27, 9], One).format_cells(&sudoku), "r24c1");
+    }
+    #[test]
+    fn test_xy_chain_as_aic() {
+        let sudoku = xy_chain_grid();
+        let step = find_aic(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(36, One)]);
+        assert_eq!(step.get_description(), "(1=2)r1c1-(2=3)r1c5-(3=1)r5c5");
+    }
+    #[test]
+    fn test_implications() {
+        let sudoku = xy_chain_grid();
+        let graph = ChainGraph::new(&sudoku, false);
+        let start = graph.find_node(&[0], One).unwrap();
+        let end = graph.find_node(&[40], Three).unwrap();
+        let implications = graph.implications(start, true);
+        assert!(implications.contains((end, true)));
+        assert_eq!(
+            graph.format_chain(&sudoku, &implications, (end, true)),
+            "(1-2)r1c1=(2-3)r1c5=(3)r5c5"
+        );
+    }
+    #[test]
+    fn test_chains_are_sound_on_a_puzzle() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let finders: [fn(&Sudoku) -> Option<Step>; 5] = [
+            find_aic,
+            find_continuous_nice_loop,
+            find_discontinuous_nice_loop,
+            find_cell_forcing_chain,
+            find_region_forcing_chain,
+        ];
+        for finder in finders.iter() {
+            if let Some(step) = finder(&sudoku) {
+                assert_sound(&sudoku, &step);
+            }
+        }
+    }
+    #[test]
+    fn test_cell_forcing_chain() {
+        let sudoku = xy_chain_grid();
+        let step = find_cell_forcing_chain(&sudoku).unwrap();
+        assert_eq!(step.get_technique(), &Technique::CellForcingChain);
+        assert!(step.get_description().starts_with("r1c1 is 12: "));
+    }
+}
@@ -0,0 +1,144 @@
+// killer techniques: the digits a cage sum allows and the 45 rule, where a house minus the cages
+// inside it leaves cells with a known total
+use super::{format_cells, rule_eliminations, Step, Technique};
+use crate::datastructures::cage::Cage;
+use crate::datastructures::constraint::Constraint;
+use crate::datastructures::sudoku::Sudoku;
+
+// the cells and sum of every cage
+fn cages(sudoku: &Sudoku) -> Vec<(Vec<usize>, usize)> {
+    sudoku
+        .get_cages()
+        .map(|cage| (cage.get_cells().clone(), *cage.get_sum()))
+        .collect()
+}
+
+// candidates that appear in no combination of digits that fills a cage
+pub fn find_cage_combination(sudoku: &Sudoku) -> Option<Step> {
+    for cage in sudoku.get_cages() {
+        let eliminations = rule_eliminations(sudoku, cage);
+        if !eliminations.is_empty() {
+            return Some(Step::new(
+                Technique::CageCombination,
+                Vec::new(),
+                eliminations,
+                format!(
+                    "{} adds up to {}",
+                    format_cells(sudoku, cage.get_cells()),
+                    cage.get_sum()
+                ),
+            ));
+        }
+    }
+    None
+}
+
+// the cells of a house outside the cages that fit in it (innies) add up to the house total minus
+// those cages; when cages cover the whole house the cells the remaining cages stick out of it
+// (outies) have a known total as well, but they may repeat digits
+fn house_remainders(sudoku: &Sudoku) -> Vec<(String, Cage)> {
+    let total: usize = sudoku.get_digits().iter().map(|d| d.to_usize()).sum();
+    let cages = cages(sudoku);
+    let mut result = Vec::new();
+    for house in sudoku.get_houses() {
+        let cells = house.get_cells();
+        let inside: Vec<&(Vec<usize>, usize)> = cages
+            .iter()
+            .filter(|(cage, _)| cage.iter().all(|i| cells.contains(i)))
+            .collect();
+        let crossing: Vec<&(Vec<usize>, usize)> = cages
+            .iter()
+            .filter(|(cage, _)| {
+                cage.iter().any(|i| cells.contains(i)) && !cage.iter().all(|i| cells.contains(i))
+            })
+            .collect();
+        let inside_sum: usize = inside.iter().map(|(_, sum)| sum).sum();
+        if inside_sum > total {
+            continue;
+        }
+        let innies: Vec<usize> = cells
+            .iter()
+            .copied()
+            .filter(|i| !inside.iter().any(|(cage, _)| cage.contains(i)))
+            .collect();
+        if !innies.is_empty() && !inside.is_empty() {
+            result.push((
+                format!("innies of {}", house.get_name()),
+                Cage::new(innies.clone(), total - inside_sum),
+            ));
+        }
+        let covered = innies
+            .iter()
+            .all(|i| crossing.iter().any(|(cage, _)| cage.contains(i)));
+        let crossing_sum: usize = crossing.iter().map(|(_, sum)| sum).sum();
+        if crossing.is_empty() || !covered || crossing_sum + inside_sum < total {
+            continue;
+        }
+        let mut outies: Vec<usize> = crossing
+            .iter()
+            .flat_map(|(cage, _)| cage.iter().copied())
+            .filter(|i| !cells.contains(i))
+            .collect();
+        outies.sort_unstable();
+        result.push((
+            format!("outies of {}", house.get_name()),
+            Cage::new_with_repeats(outies, crossing_sum + inside_sum - total),
+        ));
+    }
+    result
+}
+
+pub fn find_innies_outies(sudoku: &Sudoku) -> Option<Step> {
+    for (name, cage) in house_remainders(sudoku) {
+        let eliminations = rule_eliminations(sudoku, &cage);
+        if !eliminations.is_empty() {
+            return Some(Step::new(
+                Technique::InniesOuties,
+                Vec::new(),
+                eliminations,
+                format!(
+                    "{} {} add up to {}",
+                    name,
+                    format_cells(sudoku, cage.get_cells()),
+                    cage.get_sum()
+                ),
+            ));
+        }
+    }
+    None
+}
+
+pub fn find_step(sudoku: &Sudoku) -> Option<Step> {
+    find_cage_combination(sudoku).or_else(|| find_innies_outies(sudoku))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::Digit;
+    use Digit::*;
+
+    #[test]
+    fn test_cage_combination() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_cage(Cage::new(vec![0, 1], 10));
+        // the cage allowed nine before the one next to it was ruled out
+        sudoku.remove_candidate(1, One);
+        let step = find_cage_combination(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(0, Nine)]);
+        assert_eq!(step.get_description(), "r1c1,r1c2 adds up to 10");
+    }
+    #[test]
+    fn test_innies() {
+        let mut sudoku = Sudoku::new(4, 4, 2, 2);
+        sudoku.add_cage(Cage::new(vec![0, 1], 3));
+        sudoku.add_cage(Cage::new(vec![2, 3, 7], 9));
+        // the last two cells of the first row hold seven between them, three and four
+        let step = find_innies_outies(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(2, Two), (3, Two)]);
+        assert_eq!(
+            step.get_description(),
+            "innies of row 1 r1c3,r1c4 add up to 7"
+        );
+    }
+}
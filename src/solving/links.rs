@@ -0,0 +1,189 @@
+// strong and weak links between the candidates of a single digit
+// two candidates are weakly linked when they share a house, so they can not both be true, and
+// strongly linked when they are the only two places left for the digit in a house, so one of
+// them has to be true
+use super::cells_with_candidate;
+use crate::datastructures::digit::Digit;
+use crate::datastructures::house::{House, HouseKind};
+use crate::datastructures::sudoku::Sudoku;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Strong,
+    Weak,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HouseShape {
+    Row,
+    Column,
+    Section,
+}
+
+#[derive(Debug, Clone)]
+pub struct Link {
+    first: usize,
+    second: usize,
+    kind: LinkKind,
+    // the house the link is strong in, or the first house the cells share for a weak link
+    shape: HouseShape,
+}
+
+impl Link {
+    pub fn get_first(&self) -> &usize {
+        &self.first
+    }
+    pub fn get_second(&self) -> &usize {
+        &self.second
+    }
+    pub fn get_kind(&self) -> &LinkKind {
+        &self.kind
+    }
+    pub fn get_shape(&self) -> &HouseShape {
+        &self.shape
+    }
+    pub fn other(&self, cell: usize) -> usize {
+        if cell == self.first {
+            self.second
+        } else {
+            self.first
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkGraph {
+    digit: Digit,
+    nodes: Vec<usize>,
+    links: Vec<Link>,
+    // positions in links for every linked pair, smallest cell first
+    lookup: HashMap<(usize, usize), usize>,
+    adjacent: HashMap<usize, Vec<usize>>,
+}
+
+fn key(first: usize, second: usize) -> (usize, usize) {
+    (first.min(second), first.max(second))
+}
+
+// every house that is not a line counts as a section
+pub fn shape_of_house(house: &House) -> HouseShape {
+    match house.get_kind() {
+        HouseKind::Row => HouseShape::Row,
+        HouseKind::Column => HouseShape::Column,
+        _ => HouseShape::Section,
+    }
+}
+
+impl LinkGraph {
+    pub fn new(sudoku: &Sudoku, digit: Digit) -> Self {
+        let nodes: Vec<usize> = sudoku
+            .cells()
+            .filter(|c| c.is_empty() && c.has_candidate(digit))
+            .map(|c| *c.get_index())
+            .collect();
+        let mut links: Vec<Link> = Vec::new();
+        let mut lookup: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut adjacent: HashMap<usize, Vec<usize>> = HashMap::new();
+        for house in sudoku.get_houses() {
+            let shape = shape_of_house(house);
+            let positions = cells_with_candidate(sudoku, house.get_cells(), digit);
+            let kind = if positions.len() == 2 {
+                LinkKind::Strong
+            } else {
+                LinkKind::Weak
+            };
+            for (position, first) in positions.iter().enumerate() {
+                for second in &positions[position + 1..] {
+                    match lookup.get(&key(*first, *second)) {
+                        Some(position) => {
+                            let link = &mut links[*position];
+                            if kind == LinkKind::Strong && link.kind == LinkKind::Weak {
+                                link.kind = kind;
+                                link.shape = shape;
+                            }
+                        }
+                        None => {
+                            lookup.insert(key(*first, *second), links.len());
+                            adjacent.entry(*first).or_default().push(links.len());
+                            adjacent.entry(*second).or_default().push(links.len());
+                            links.push(Link {
+                                first: *first,
+                                second: *second,
+                                kind,
+                                shape,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        LinkGraph {
+            digit,
+            nodes,
+            links,
+            lookup,
+            adjacent,
+        }
+    }
+    pub fn get_digit(&self) -> &Digit {
+        &self.digit
+    }
+    pub fn get_nodes(&self) -> &Vec<usize> {
+        &self.nodes
+    }
+    pub fn get_links(&self) -> &Vec<Link> {
+        &self.links
+    }
+    pub fn strong_links(&self) -> impl Iterator<Item = &Link> {
+        self.links.iter().filter(|l| l.kind == LinkKind::Strong)
+    }
+    pub fn find_link(&self, first: usize, second: usize) -> Option<&Link> {
+        self.lookup
+            .get(&key(first, second))
+            .map(|position| &self.links[*position])
+    }
+    pub fn is_strong(&self, first: usize, second: usize) -> bool {
+        self.find_link(first, second)
+            .is_some_and(|l| l.kind == LinkKind::Strong)
+    }
+    // cells linked to cell by a link of the given kind, a strong link can always be used as a
+    // weak one so asking for weak links returns every linked cell
+    pub fn neighbours(&self, cell: usize, kind: LinkKind) -> Vec<usize> {
+        self.adjacent
+            .get(&cell)
+            .map_or(&[] as &[usize], |positions| positions)
+            .iter()
+            .map(|position| &self.links[*position])
+            .filter(|l| kind == LinkKind::Weak || l.kind == LinkKind::Strong)
+            .map(|l| l.other(cell))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_graph_of_empty_grid_has_no_strong_links() {
+        let sudoku = Sudoku::new(9, 9, 3, 3);
+        let graph = LinkGraph::new(&sudoku, Digit::One);
+        assert_eq!(graph.get_nodes().len(), 81);
+        assert_eq!(graph.strong_links().count(), 0);
+        assert_eq!(graph.neighbours(40, LinkKind::Weak).len(), 20);
+    }
+    #[test]
+    fn test_strong_link_in_a_row() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        for index in 2..9 {
+            sudoku.remove_candidate(index, Digit::One);
+        }
+        let graph = LinkGraph::new(&sudoku, Digit::One);
+        let strong: Vec<&Link> = graph.strong_links().collect();
+        assert_eq!(strong.len(), 1);
+        assert_eq!(strong[0].get_shape(), &HouseShape::Row);
+        assert!(graph.is_strong(1, 0));
+        assert_eq!(graph.neighbours(0, LinkKind::Strong), vec![1]);
+    }
+}
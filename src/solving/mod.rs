@@ -0,0 +1,199 @@
+pub mod als;
+pub mod basic;
+pub mod chains;
+pub mod killer;
+pub mod links;
+pub mod single_digit;
+pub mod step;
+pub mod trace;
+pub mod uniqueness;
+pub mod variants;
+
+pub use step::{Step, Technique};
+pub use trace::{solve_with_trace, SolveTrace, TraceEntry};
+
+use crate::datastructures::constraint::Constraint;
+use crate::datastructures::digit::{Digit, DigitHashSet};
+use crate::datastructures::house::House;
+use crate::datastructures::sudoku::Sudoku;
+
+#[derive(Debug, Clone, Default)]
+pub struct SolverOptions {
+    assume_unique_solution: bool,
+}
+
+impl SolverOptions {
+    pub fn new() -> Self {
+        SolverOptions {
+            assume_unique_solution: false,
+        }
+    }
+    pub fn get_assume_unique_solution(&self) -> &bool {
+        &self.assume_unique_solution
+    }
+    // enables the uniqueness based techniques, only do this for puzzles with a single solution;
+    // they are never used on variants
+    pub fn set_assume_unique_solution(&mut self, assume_unique_solution: bool) {
+        self.assume_unique_solution = assume_unique_solution;
+    }
+}
+
+// finds the next deduction using the techniques enabled in options
+pub fn find_step(sudoku: &Sudoku, options: &SolverOptions) -> Option<Step> {
+    if let Some(step) = basic::find_step(sudoku) {
+        return Some(step);
+    }
+    if let Some(step) = killer::find_step(sudoku) {
+        return Some(step);
+    }
+    if let Some(step) = variants::find_step(sudoku) {
+        return Some(step);
+    }
+    if let Some(step) = single_digit::find_step(sudoku) {
+        return Some(step);
+    }
+    if *options.get_assume_unique_solution() && sudoku.is_standard() {
+        if let Some(step) = uniqueness::find_step(sudoku) {
+            return Some(step);
+        }
+    }
+    if let Some(step) = als::find_step(sudoku) {
+        return Some(step);
+    }
+    chains::find_step(sudoku)
+}
+
+pub fn candidates(sudoku: &Sudoku, index: usize) -> &DigitHashSet {
+    sudoku.get_cell(index).unwrap().get_candidates()
+}
+
+// the empty cells of a house that still have digit as a candidate
+pub fn cells_with_candidate(sudoku: &Sudoku, house: &[usize], digit: Digit) -> Vec<usize> {
+    house
+        .iter()
+        .copied()
+        .filter(|i| {
+            let cell = sudoku.get_cell(*i).unwrap();
+            cell.is_empty() && cell.has_candidate(digit)
+        })
+        .collect()
+}
+
+// empty cells, not in cells, that see every one of cells
+pub fn common_peers(sudoku: &Sudoku, cells: &[usize]) -> Vec<usize> {
+    sudoku
+        .cells()
+        .map(|c| *c.get_index())
+        .filter(|i| sudoku.get_cell(*i).unwrap().is_empty() && !cells.contains(i))
+        .filter(|i| cells.iter().all(|c| sudoku.sees(*i, *c)))
+        .collect()
+}
+
+// the candidates of its cells a rule rules out given the current ones
+pub fn rule_eliminations(sudoku: &Sudoku, rule: &dyn Constraint) -> Vec<(usize, Digit)> {
+    let mut candidates: Vec<DigitHashSet> = (0..sudoku.get_grid_dimensions().get_data_size())
+        .map(|i| sudoku.get_cell(i).unwrap().get_candidates().clone())
+        .collect();
+    rule.restrict_candidates(sudoku, &mut candidates);
+    let mut result = Vec::new();
+    for index in rule.get_cells() {
+        let cell = sudoku.get_cell(index).unwrap();
+        if !cell.is_empty() {
+            continue;
+        }
+        for digit in sudoku.get_digits() {
+            if cell.has_candidate(digit) && !candidates[index].contains(&digit) {
+                result.push((index, digit));
+            }
+        }
+    }
+    result
+}
+
+// every pairing of a house that is not a line, such as a section, with a row or column
+pub fn section_line_pairs(sudoku: &Sudoku) -> Vec<(&House, &House)> {
+    let houses = sudoku.get_houses();
+    let mut result = Vec::new();
+    for section in houses.iter().filter(|house| !house.is_line()) {
+        for line in houses.iter().filter(|house| house.is_line()) {
+            result.push((section, line));
+        }
+    }
+    result
+}
+
+pub fn format_cells(sudoku: &Sudoku, cells: &[usize]) -> String {
+    cells
+        .iter()
+        .map(|i| sudoku.get_cell(*i).unwrap().get_name())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+pub fn format_digits(digits: &DigitHashSet) -> String {
+    let mut sorted: Vec<usize> = digits.iter().map(|d| d.to_usize()).collect();
+    sorted.sort_unstable();
+    sorted.iter().map(|d| d.to_string()).collect()
+}
+
+// every way of choosing size items, keeping their order
+pub fn combinations<T: Clone>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    if items.len() < size {
+        return Vec::new();
+    }
+    let mut result = Vec::new();
+    for (position, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[position + 1..], size - 1) {
+            rest.insert(0, item.clone());
+            result.push(rest);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(combinations(&[1, 2, 3, 4], 2).len(), 6);
+        assert_eq!(combinations(&[1, 2, 3], 3), vec![vec![1, 2, 3]]);
+        assert_eq!(combinations(&[1, 2], 3).len(), 0);
+    }
+    #[test]
+    fn test_common_peers() {
+        let sudoku = Sudoku::new(9, 9, 3, 3);
+        // r1c1 and r2c4 share no house, their common peers are r1c4-r1c6 and r2c1-r2c3
+        assert_eq!(common_peers(&sudoku, &[0, 12]), vec![3, 4, 5, 9, 10, 11]);
+    }
+    #[test]
+    fn test_uniqueness_needs_to_be_enabled() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let pair: DigitHashSet = [Digit::One, Digit::Two].iter().copied().collect();
+        for index in [0, 3, 9] {
+            sudoku
+                .get_mut_cell(index)
+                .unwrap()
+                .set_candidates(pair.clone());
+        }
+        // keep the pairs from being a naked pair or locked candidates with anything to remove
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        let mut others = dimensions.get_indices_for_row(0);
+        others.extend(dimensions.get_indices_for_column(0));
+        others.extend(dimensions.get_indices_for_section(0));
+        for index in others.into_iter().filter(|i| ![0, 3, 9].contains(i)) {
+            sudoku.remove_candidate(index, Digit::One);
+            sudoku.remove_candidate(index, Digit::Two);
+        }
+        let mut options = SolverOptions::new();
+        let step = find_step(&sudoku, &options);
+        assert!(step.is_none_or(|s| !s.get_technique().assumes_uniqueness()));
+        options.set_assume_unique_solution(true);
+        let step = find_step(&sudoku, &options).unwrap();
+        assert_eq!(step.get_technique(), &Technique::UniqueRectangleType1);
+    }
+}
@@ -0,0 +1,572 @@
+// techniques that only look at the candidates of one digit, built on the link graph
+use super::links::{HouseShape, LinkGraph, LinkKind};
+use super::{common_peers, format_cells, Step, Technique};
+use crate::datastructures::digit::Digit;
+use crate::datastructures::sudoku::Sudoku;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const MAX_CYCLE_LENGTH: usize = 8;
+
+fn graphs(sudoku: &Sudoku) -> Vec<LinkGraph> {
+    sudoku
+        .get_digits()
+        .into_iter()
+        .map(|digit| LinkGraph::new(sudoku, digit))
+        .collect()
+}
+
+fn remove_digit(sudoku: &Sudoku, cells: &[usize], digit: Digit) -> Vec<(usize, Digit)> {
+    let mut sorted: Vec<usize> = cells
+        .iter()
+        .copied()
+        .filter(|i| sudoku.get_cell(*i).unwrap().has_candidate(digit))
+        .collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    sorted.into_iter().map(|i| (i, digit)).collect()
+}
+
+// chain notation for a single digit, like 1 r1c1=r1c5-r4c5=r4c2
+fn format_chain(sudoku: &Sudoku, digit: Digit, cells: &[usize], kinds: &[LinkKind]) -> String {
+    let mut result = format!("{} {}", digit.to_usize(), format_cells(sudoku, &cells[..1]));
+    for (cell, kind) in cells[1..].iter().zip(kinds) {
+        result.push(if *kind == LinkKind::Strong { '=' } else { '-' });
+        result.push_str(&format_cells(sudoku, &[*cell]));
+    }
+    result
+}
+
+#[derive(PartialEq)]
+enum FishShape {
+    Skyscraper,
+    Kite,
+    Turbot,
+}
+
+fn same_line(sudoku: &Sudoku, first: usize, second: usize) -> bool {
+    let a = sudoku.get_cell(first).unwrap();
+    let b = sudoku.get_cell(second).unwrap();
+    a.get_row() == b.get_row() || a.get_column() == b.get_column()
+}
+
+fn same_section(sudoku: &Sudoku, first: usize, second: usize) -> bool {
+    sudoku.get_cell(first).unwrap().get_section() == sudoku.get_cell(second).unwrap().get_section()
+}
+
+// two strong links connected by a weak link: one of the outer ends has to be the digit
+fn find_strong_weak_strong(sudoku: &Sudoku, wanted: FishShape) -> Option<Step> {
+    for graph in graphs(sudoku) {
+        let digit = *graph.get_digit();
+        let strong: Vec<_> = graph.strong_links().collect();
+        for (position, first) in strong.iter().enumerate() {
+            for second in &strong[position + 1..] {
+                let ends = [
+                    (*first.get_first(), *first.get_second()),
+                    (*first.get_second(), *first.get_first()),
+                ];
+                let other_ends = [
+                    (*second.get_first(), *second.get_second()),
+                    (*second.get_second(), *second.get_first()),
+                ];
+                for (a, b) in ends.iter() {
+                    for (c, d) in other_ends.iter() {
+                        let cells = [*a, *b, *c, *d];
+                        let distinct: HashSet<usize> = cells.iter().copied().collect();
+                        if distinct.len() != 4 || !sudoku.sees(*b, *c) {
+                            continue;
+                        }
+                        let shapes = (*first.get_shape(), *second.get_shape());
+                        let shape = match shapes {
+                            (HouseShape::Row, HouseShape::Row)
+                            | (HouseShape::Column, HouseShape::Column)
+                                if same_line(sudoku, *b, *c) =>
+                            {
+                                FishShape::Skyscraper
+                            }
+                            (HouseShape::Row, HouseShape::Column)
+                            | (HouseShape::Column, HouseShape::Row)
+                                if same_section(sudoku, *b, *c) =>
+                            {
+                                FishShape::Kite
+                            }
+                            _ => FishShape::Turbot,
+                        };
+                        if shape != wanted {
+                            continue;
+                        }
+                        let targets: Vec<usize> = common_peers(sudoku, &[*a, *d])
+                            .into_iter()
+                            .filter(|i| !cells.contains(i))
+                            .collect();
+                        let eliminations = remove_digit(sudoku, &targets, digit);
+                        if eliminations.is_empty() {
+                            continue;
+                        }
+                        let technique = match shape {
+                            FishShape::Skyscraper => Technique::Skyscraper,
+                            FishShape::Kite => Technique::TwoStringKite,
+                            FishShape::Turbot => Technique::TurbotFish,
+                        };
+                        let kinds = [LinkKind::Strong, LinkKind::Weak, LinkKind::Strong];
+                        return Some(Step::new(
+                            technique,
+                            Vec::new(),
+                            eliminations,
+                            format_chain(sudoku, digit, &cells, &kinds),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn find_skyscraper(sudoku: &Sudoku) -> Option<Step> {
+    find_strong_weak_strong(sudoku, FishShape::Skyscraper)
+}
+
+pub fn find_two_string_kite(sudoku: &Sudoku) -> Option<Step> {
+    find_strong_weak_strong(sudoku, FishShape::Kite)
+}
+
+pub fn find_turbot_fish(sudoku: &Sudoku) -> Option<Step> {
+    find_strong_weak_strong(sudoku, FishShape::Turbot)
+}
+
+// the cells connected by strong links split into two colors, exactly one color is true
+struct Cluster {
+    colors: [Vec<usize>; 2],
+}
+
+impl Cluster {
+    fn contains(&self, cell: usize) -> bool {
+        self.colors.iter().any(|color| color.contains(&cell))
+    }
+}
+
+fn find_clusters(graph: &LinkGraph) -> Vec<Cluster> {
+    let mut color_of: HashMap<usize, usize> = HashMap::new();
+    let mut clusters = Vec::new();
+    for start in graph.get_nodes() {
+        if color_of.contains_key(start) || graph.neighbours(*start, LinkKind::Strong).is_empty() {
+            continue;
+        }
+        let mut colors: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+        let mut consistent = true;
+        let mut queue = VecDeque::new();
+        color_of.insert(*start, 0);
+        queue.push_back(*start);
+        while let Some(cell) = queue.pop_front() {
+            let color = color_of[&cell];
+            colors[color].push(cell);
+            for next in graph.neighbours(cell, LinkKind::Strong) {
+                match color_of.get(&next) {
+                    Some(existing) => consistent &= *existing != color,
+                    None => {
+                        color_of.insert(next, 1 - color);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        if consistent {
+            clusters.push(Cluster { colors });
+        }
+    }
+    clusters
+}
+
+fn sees_any(sudoku: &Sudoku, cell: usize, cells: &[usize]) -> bool {
+    cells.iter().any(|c| sudoku.sees(cell, *c))
+}
+
+fn color_description(sudoku: &Sudoku, digit: Digit, cluster: &Cluster) -> String {
+    format!(
+        "{} colors {} / {}",
+        digit.to_usize(),
+        format_cells(sudoku, &cluster.colors[0]),
+        format_cells(sudoku, &cluster.colors[1])
+    )
+}
+
+// color wrap: two cells of one color see each other so that color is false
+// color trap: a cell that sees both colors can not hold the digit
+pub fn find_simple_coloring(sudoku: &Sudoku) -> Option<Step> {
+    for graph in graphs(sudoku) {
+        let digit = *graph.get_digit();
+        for cluster in find_clusters(&graph) {
+            for color in cluster.colors.iter() {
+                let wrapped = color.iter().any(|cell| sees_any(sudoku, *cell, color));
+                if wrapped {
+                    return Some(Step::new(
+                        Technique::SimpleColoring,
+                        Vec::new(),
+                        remove_digit(sudoku, color, digit),
+                        format!("{}, color wrap", color_description(sudoku, digit, &cluster)),
+                    ));
+                }
+            }
+            let trapped: Vec<usize> = graph
+                .get_nodes()
+                .iter()
+                .copied()
+                .filter(|cell| !cluster.contains(*cell))
+                .filter(|cell| cluster.colors.iter().all(|c| sees_any(sudoku, *cell, c)))
+                .collect();
+            if !trapped.is_empty() {
+                return Some(Step::new(
+                    Technique::SimpleColoring,
+                    Vec::new(),
+                    remove_digit(sudoku, &trapped, digit),
+                    format!("{}, color trap", color_description(sudoku, digit, &cluster)),
+                ));
+            }
+        }
+    }
+    None
+}
+
+// two clusters where a color of one sees a color of the other: those two colors can not both be
+// true, so one of their opposite colors is
+pub fn find_multi_coloring(sudoku: &Sudoku) -> Option<Step> {
+    for graph in graphs(sudoku) {
+        let digit = *graph.get_digit();
+        let clusters = find_clusters(&graph);
+        for (first_position, first) in clusters.iter().enumerate() {
+            for (second_position, second) in clusters.iter().enumerate() {
+                if first_position == second_position {
+                    continue;
+                }
+                for a in 0..2 {
+                    let seen: Vec<usize> = (0..2)
+                        .filter(|b| {
+                            first.colors[a]
+                                .iter()
+                                .any(|cell| sees_any(sudoku, *cell, &second.colors[*b]))
+                        })
+                        .collect();
+                    let description = format!(
+                        "{} colors {} / {} and {} / {}",
+                        digit.to_usize(),
+                        format_cells(sudoku, &first.colors[0]),
+                        format_cells(sudoku, &first.colors[1]),
+                        format_cells(sudoku, &second.colors[0]),
+                        format_cells(sudoku, &second.colors[1])
+                    );
+                    if seen.len() == 2 {
+                        return Some(Step::new(
+                            Technique::MultiColoring,
+                            Vec::new(),
+                            remove_digit(sudoku, &first.colors[a], digit),
+                            description,
+                        ));
+                    }
+                    if let Some(b) = seen.first() {
+                        let opposite_first = &first.colors[1 - a];
+                        let opposite_second = &second.colors[1 - b];
+                        let targets: Vec<usize> = graph
+                            .get_nodes()
+                            .iter()
+                            .copied()
+                            .filter(|cell| !first.contains(*cell) && !second.contains(*cell))
+                            .filter(|cell| sees_any(sudoku, *cell, opposite_first))
+                            .filter(|cell| sees_any(sudoku, *cell, opposite_second))
+                            .collect();
+                        if !targets.is_empty() {
+                            return Some(Step::new(
+                                Technique::MultiColoring,
+                                Vec::new(),
+                                remove_digit(sudoku, &targets, digit),
+                                description,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+// continuous x-cycle: an alternating loop, every weak link in it behaves like a strong one so
+// the digit can be removed from the rest of the houses of the weak links
+fn extend_cycle(
+    sudoku: &Sudoku,
+    graph: &LinkGraph,
+    path: &mut Vec<usize>,
+    kinds: &mut Vec<LinkKind>,
+) -> Option<Step> {
+    let last = *path.last().unwrap();
+    let next_kind = if kinds.last() == Some(&LinkKind::Strong) {
+        LinkKind::Weak
+    } else {
+        LinkKind::Strong
+    };
+    for next in graph.neighbours(last, next_kind) {
+        if next == path[0] && next_kind == LinkKind::Weak && path.len() >= 4 {
+            let mut cells = path.clone();
+            cells.push(next);
+            kinds.push(next_kind);
+            let step = cycle_step(sudoku, graph, &cells, kinds);
+            kinds.pop();
+            if step.is_some() {
+                return step;
+            }
+            continue;
+        }
+        if path.contains(&next) || path.len() >= MAX_CYCLE_LENGTH {
+            continue;
+        }
+        // a weak link is only useful if a strong link can follow it
+        if next_kind == LinkKind::Weak && graph.neighbours(next, LinkKind::Strong).is_empty() {
+            continue;
+        }
+        path.push(next);
+        kinds.push(next_kind);
+        let step = extend_cycle(sudoku, graph, path, kinds);
+        path.pop();
+        kinds.pop();
+        if step.is_some() {
+            return step;
+        }
+    }
+    None
+}
+
+fn cycle_step(
+    sudoku: &Sudoku,
+    graph: &LinkGraph,
+    cells: &[usize],
+    kinds: &[LinkKind],
+) -> Option<Step> {
+    let digit = *graph.get_digit();
+    let mut targets = Vec::new();
+    for (position, kind) in kinds.iter().enumerate() {
+        if *kind != LinkKind::Weak {
+            continue;
+        }
+        let (a, b) = (cells[position], cells[position + 1]);
+        for house in sudoku.get_houses() {
+            if house.contains(a) && house.contains(b) {
+                targets.extend(house.get_cells().iter().filter(|i| !cells.contains(i)));
+            }
+        }
+    }
+    let eliminations = remove_digit(sudoku, &targets, digit);
+    if eliminations.is_empty() {
+        return None;
+    }
+    Some(Step::new(
+        Technique::XCycle,
+        Vec::new(),
+        eliminations,
+        format!(
+            "continuous loop {}",
+            format_chain(sudoku, digit, cells, kinds)
+        ),
+    ))
+}
+
+// discontinuous x-cycle: assuming a value for a cell leads back to the opposite value
+fn find_contradiction(
+    sudoku: &Sudoku,
+    graph: &LinkGraph,
+    start: usize,
+    value: bool,
+) -> Option<Step> {
+    let digit = *graph.get_digit();
+    let mut parent: HashMap<(usize, bool), (usize, bool)> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((start, value));
+    parent.insert((start, value), (start, value));
+    while let Some((cell, is_true)) = queue.pop_front() {
+        let kind = if is_true {
+            LinkKind::Weak
+        } else {
+            LinkKind::Strong
+        };
+        for next in graph.neighbours(cell, kind) {
+            let state = (next, !is_true);
+            if parent.contains_key(&state) {
+                continue;
+            }
+            parent.insert(state, (cell, is_true));
+            if state == (start, !value) {
+                let mut cells = vec![start];
+                let mut current = state;
+                while current != (start, value) {
+                    current = parent[&current];
+                    cells.push(current.0);
+                }
+                cells.reverse();
+                let kinds: Vec<LinkKind> = (1..cells.len())
+                    .map(|position| {
+                        if (position % 2 == 1) == value {
+                            LinkKind::Weak
+                        } else {
+                            LinkKind::Strong
+                        }
+                    })
+                    .collect();
+                let (placements, eliminations) = if value {
+                    (Vec::new(), vec![(start, digit)])
+                } else {
+                    (vec![(start, digit)], Vec::new())
+                };
+                return Some(Step::new(
+                    Technique::XCycle,
+                    placements,
+                    eliminations,
+                    format!(
+                        "discontinuous loop {}",
+                        format_chain(sudoku, digit, &cells, &kinds)
+                    ),
+                ));
+            }
+            queue.push_back(state);
+        }
+    }
+    None
+}
+
+pub fn find_x_cycle(sudoku: &Sudoku) -> Option<Step> {
+    let graphs = graphs(sudoku);
+    for graph in &graphs {
+        for link in graph.strong_links() {
+            let mut path = vec![*link.get_first(), *link.get_second()];
+            let mut kinds = vec![LinkKind::Strong];
+            if let Some(step) = extend_cycle(sudoku, graph, &mut path, &mut kinds) {
+                return Some(step);
+            }
+        }
+    }
+    for graph in &graphs {
+        for start in graph.get_nodes() {
+            for value in [false, true] {
+                if let Some(step) = find_contradiction(sudoku, graph, *start, value) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn find_step(sudoku: &Sudoku) -> Option<Step> {
+    let finders: [fn(&Sudoku) -> Option<Step>; 6] = [
+        find_skyscraper,
+        find_two_string_kite,
+        find_turbot_fish,
+        find_simple_coloring,
+        find_multi_coloring,
+        find_x_cycle,
+    ];
+    finders.iter().find_map(|finder| finder(sudoku))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Digit::*;
+
+    fn keep_only(sudoku: &mut Sudoku, house: Vec<usize>, keep: &[usize]) {
+        for index in house {
+            if !keep.contains(&index) {
+                sudoku.remove_candidate(index, One);
+            }
+        }
+    }
+
+    fn skyscraper_grid() -> Sudoku {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(0), &[0, 4]);
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(4), &[36, 39]);
+        sudoku
+    }
+
+    #[test]
+    fn test_skyscraper() {
+        let sudoku = skyscraper_grid();
+        let step = find_skyscraper(&sudoku).unwrap();
+        assert_eq!(
+            step.get_eliminations(),
+            &vec![(12, One), (21, One), (31, One), (49, One)]
+        );
+    }
+    #[test]
+    fn test_two_string_kite() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(0), &[1, 6]);
+        keep_only(&mut sudoku, dimensions.get_indices_for_column(0), &[18, 54]);
+        assert_eq!(find_skyscraper(&sudoku), None);
+        let step = find_two_string_kite(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(60, One)]);
+    }
+    #[test]
+    fn test_turbot_fish() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        // the weak link r1c7 - r3c9 is in a box but not in a line
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(0), &[1, 6]);
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(2), &[20, 26]);
+        assert_eq!(find_skyscraper(&sudoku), None);
+        assert_eq!(find_two_string_kite(&sudoku), None);
+        let step = find_turbot_fish(&sudoku).unwrap();
+        assert_eq!(step.get_technique(), &Technique::TurbotFish);
+        assert_eq!(
+            step.get_eliminations(),
+            &vec![(9, One), (10, One), (11, One)]
+        );
+    }
+    #[test]
+    fn test_simple_coloring_trap() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        keep_only(&mut sudoku, dimensions.get_indices_for_column(0), &[0, 45]);
+        keep_only(
+            &mut sudoku,
+            dimensions.get_indices_for_section(3),
+            &[45, 29],
+        );
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(3), &[29, 34]);
+        let step = find_simple_coloring(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(7, One)]);
+    }
+    #[test]
+    fn test_multi_coloring() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        keep_only(&mut sudoku, dimensions.get_indices_for_column(0), &[0, 54]);
+        keep_only(&mut sudoku, dimensions.get_indices_for_column(2), &[11, 65]);
+        let step = find_multi_coloring(&sudoku).unwrap();
+        assert_eq!(
+            step.get_eliminations(),
+            &vec![(55, One), (64, One), (73, One)]
+        );
+    }
+    #[test]
+    fn test_continuous_x_cycle() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let dimensions = sudoku.get_grid_dimensions().clone();
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(0), &[1, 6]);
+        keep_only(&mut sudoku, dimensions.get_indices_for_row(4), &[37, 42]);
+        let step = find_x_cycle(&sudoku).unwrap();
+        assert_eq!(step.get_technique(), &Technique::XCycle);
+        assert_eq!(step.get_eliminations().len(), 14);
+        assert!(step
+            .get_eliminations()
+            .iter()
+            .all(|(i, _)| i % 9 == 1 || i % 9 == 6));
+    }
+    #[test]
+    fn test_discontinuous_x_cycle() {
+        let sudoku = skyscraper_grid();
+        let step = find_x_cycle(&sudoku).unwrap();
+        assert!(step.get_description().starts_with("discontinuous"));
+        assert_eq!(step.get_eliminations().len(), 1);
+        assert!([12, 21, 31, 49].contains(&step.get_eliminations()[0].0));
+    }
+}
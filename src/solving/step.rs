@@ -0,0 +1,206 @@
+use crate::datastructures::digit::Digit;
+use crate::datastructures::sudoku::Sudoku;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Technique {
+    FullHouse,
+    NakedSingle,
+    HiddenSingle,
+    LockedCandidatesPointing,
+    LockedCandidatesClaiming,
+    NakedPair,
+    NakedTriple,
+    NakedQuad,
+    HiddenPair,
+    HiddenTriple,
+    HiddenQuad,
+    CageCombination,
+    InniesOuties,
+    VariantRule,
+    Skyscraper,
+    TwoStringKite,
+    TurbotFish,
+    SimpleColoring,
+    MultiColoring,
+    XCycle,
+    Aic,
+    GroupedAic,
+    ContinuousNiceLoop,
+    DiscontinuousNiceLoop,
+    CellForcingChain,
+    RegionForcingChain,
+    AlsXz,
+    AlsXyWing,
+    DeathBlossom,
+    SueDeCoq,
+    UniqueRectangleType1,
+    UniqueRectangleType2,
+    UniqueRectangleType3,
+    UniqueRectangleType4,
+    UniqueRectangleType5,
+    UniqueRectangleType6,
+    HiddenUniqueRectangle,
+    BugPlusOne,
+}
+
+impl Technique {
+    pub fn get_name(&self) -> &'static str {
+        use Technique::*;
+
+        match self {
+            FullHouse => "Full House",
+            NakedSingle => "Naked Single",
+            HiddenSingle => "Hidden Single",
+            LockedCandidatesPointing => "Locked Candidates (Pointing)",
+            LockedCandidatesClaiming => "Locked Candidates (Claiming)",
+            NakedPair => "Naked Pair",
+            NakedTriple => "Naked Triple",
+            NakedQuad => "Naked Quad",
+            HiddenPair => "Hidden Pair",
+            HiddenTriple => "Hidden Triple",
+            HiddenQuad => "Hidden Quad",
+            CageCombination => "Cage Combination",
+            InniesOuties => "Innies and Outies",
+            VariantRule => "Variant Rule",
+            Skyscraper => "Skyscraper",
+            TwoStringKite => "2-String Kite",
+            TurbotFish => "Turbot Fish",
+            SimpleColoring => "Simple Coloring",
+            MultiColoring => "Multi Coloring",
+            XCycle => "X-Cycle",
+            Aic => "AIC",
+            GroupedAic => "Grouped AIC",
+            ContinuousNiceLoop => "Continuous Nice Loop",
+            DiscontinuousNiceLoop => "Discontinuous Nice Loop",
+            CellForcingChain => "Cell Forcing Chain",
+            RegionForcingChain => "Region Forcing Chain",
+            AlsXz => "ALS-XZ",
+            AlsXyWing => "ALS-XY-Wing",
+            DeathBlossom => "Death Blossom",
+            SueDeCoq => "Sue de Coq",
+            UniqueRectangleType1 => "Unique Rectangle Type 1",
+            UniqueRectangleType2 => "Unique Rectangle Type 2",
+            UniqueRectangleType3 => "Unique Rectangle Type 3",
+            UniqueRectangleType4 => "Unique Rectangle Type 4",
+            UniqueRectangleType5 => "Unique Rectangle Type 5",
+            UniqueRectangleType6 => "Unique Rectangle Type 6",
+            HiddenUniqueRectangle => "Hidden Unique Rectangle",
+            BugPlusOne => "BUG+1",
+        }
+    }
+    // techniques that are only valid when the puzzle is known to have a single solution
+    pub fn assumes_uniqueness(&self) -> bool {
+        use Technique::*;
+
+        matches!(
+            self,
+            UniqueRectangleType1
+                | UniqueRectangleType2
+                | UniqueRectangleType3
+                | UniqueRectangleType4
+                | UniqueRectangleType5
+                | UniqueRectangleType6
+                | HiddenUniqueRectangle
+                | BugPlusOne
+        )
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.get_name())
+    }
+}
+
+// one deduction: the digits it places and the candidates it removes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    technique: Technique,
+    placements: Vec<(usize, Digit)>,
+    eliminations: Vec<(usize, Digit)>,
+    description: String,
+}
+
+impl Step {
+    pub fn new(
+        technique: Technique,
+        placements: Vec<(usize, Digit)>,
+        eliminations: Vec<(usize, Digit)>,
+        description: String,
+    ) -> Self {
+        Step {
+            technique,
+            placements,
+            eliminations,
+            description,
+        }
+    }
+    pub fn get_technique(&self) -> &Technique {
+        &self.technique
+    }
+    pub fn get_placements(&self) -> &Vec<(usize, Digit)> {
+        &self.placements
+    }
+    pub fn get_eliminations(&self) -> &Vec<(usize, Digit)> {
+        &self.eliminations
+    }
+    pub fn get_description(&self) -> &String {
+        &self.description
+    }
+    pub fn apply(&self, sudoku: &mut Sudoku) {
+        for (index, digit) in &self.placements {
+            sudoku.set_digit(*index, *digit);
+        }
+        for (index, digit) in &self.eliminations {
+            sudoku.remove_candidate(*index, *digit);
+        }
+    }
+    // the result of the step in the usual notation, r1c2=3 for placements and r1c2<>3 for
+    // eliminations
+    pub fn format_result(&self, sudoku: &Sudoku) -> String {
+        let name = |index: usize| {
+            sudoku
+                .get_cell(index)
+                .map_or(String::new(), |c| c.get_name())
+        };
+        let mut parts: Vec<String> = self
+            .placements
+            .iter()
+            .map(|(index, digit)| format!("{}={}", name(*index), digit.to_usize()))
+            .collect();
+        parts.extend(
+            self.eliminations
+                .iter()
+                .map(|(index, digit)| format!("{}<>{}", name(*index), digit.to_usize())),
+        );
+        parts.join(", ")
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.technique, self.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_step() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        let step = Step::new(
+            Technique::BugPlusOne,
+            vec![(0, Digit::One)],
+            vec![(40, Digit::Two)],
+            String::new(),
+        );
+        step.apply(&mut sudoku);
+        assert_eq!(sudoku.get_cell(0).unwrap().get_value(), &Some(Digit::One));
+        assert!(!sudoku.get_cell(1).unwrap().has_candidate(Digit::One));
+        assert!(!sudoku.get_cell(40).unwrap().has_candidate(Digit::Two));
+        assert_eq!(step.format_result(&sudoku), "r1c1=1, r5c5<>2");
+    }
+}
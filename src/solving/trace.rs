@@ -0,0 +1,215 @@
+// the full path of a human style solve, every step in order with the grid it was found in
+use super::{find_step, SolverOptions, Step};
+use crate::datastructures::digit::Digit;
+use crate::datastructures::sudoku::Sudoku;
+
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    step: Step,
+    // the grid and the candidates of every cell before the step, filled cells have no candidates
+    grid: String,
+    candidates: Vec<String>,
+    result: String,
+}
+
+fn candidate_snapshot(sudoku: &Sudoku) -> Vec<String> {
+    (0..sudoku.get_grid_dimensions().get_data_size())
+        .map(|index| {
+            let cell = sudoku.get_cell(index).unwrap();
+            let mut digits: Vec<usize> =
+                cell.get_candidates().iter().map(|d| d.to_usize()).collect();
+            digits.sort_unstable();
+            if !cell.is_empty() {
+                digits.clear();
+            }
+            digits.iter().map(|d| d.to_string()).collect()
+        })
+        .collect()
+}
+
+impl TraceEntry {
+    fn new(sudoku: &Sudoku, step: Step) -> Self {
+        TraceEntry {
+            grid: sudoku.to_line(),
+            candidates: candidate_snapshot(sudoku),
+            result: step.format_result(sudoku),
+            step,
+        }
+    }
+    pub fn get_step(&self) -> &Step {
+        &self.step
+    }
+    pub fn get_grid(&self) -> &String {
+        &self.grid
+    }
+    pub fn get_candidates(&self) -> &Vec<String> {
+        &self.candidates
+    }
+    // the placements and eliminations of the step, r1c2=3, r4c5<>6
+    pub fn get_result(&self) -> &String {
+        &self.result
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SolveTrace {
+    puzzle: String,
+    entries: Vec<TraceEntry>,
+    end: String,
+    solved: bool,
+    cell_names: Vec<String>,
+}
+
+// solves with the techniques enabled in options until the grid is full or no technique applies
+pub fn solve_with_trace(sudoku: &Sudoku, options: &SolverOptions) -> SolveTrace {
+    let mut work = sudoku.clone();
+    let mut entries = Vec::new();
+    while !work.is_solved() {
+        let step = match find_step(&work, options) {
+            Some(step) => step,
+            None => break,
+        };
+        let entry = TraceEntry::new(&work, step);
+        entry.get_step().apply(&mut work);
+        // a step that changes nothing would be found again forever
+        if work.to_line() == *entry.get_grid()
+            && candidate_snapshot(&work) == *entry.get_candidates()
+        {
+            break;
+        }
+        entries.push(entry);
+    }
+    SolveTrace {
+        puzzle: sudoku.to_line(),
+        entries,
+        end: work.to_line(),
+        solved: work.is_solved(),
+        cell_names: (0..sudoku.get_grid_dimensions().get_data_size())
+            .map(|i| sudoku.get_cell(i).unwrap().get_name())
+            .collect(),
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut result = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn json_cells(cells: &[(usize, Digit)], names: &[String]) -> String {
+    let items: Vec<String> = cells
+        .iter()
+        .map(|(index, digit)| {
+            format!(
+                "{{\"cell\": {}, \"digit\": {}}}",
+                json_string(&names[*index]),
+                digit.to_usize()
+            )
+        })
+        .collect();
+    format!("[{}]", items.join(", "))
+}
+
+impl SolveTrace {
+    pub fn get_puzzle(&self) -> &String {
+        &self.puzzle
+    }
+    pub fn get_entries(&self) -> &Vec<TraceEntry> {
+        &self.entries
+    }
+    // the grid after the last step
+    pub fn get_end(&self) -> &String {
+        &self.end
+    }
+    pub fn is_solved(&self) -> bool {
+        self.solved
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut steps: Vec<String> = Vec::new();
+        for entry in &self.entries {
+            let step = entry.get_step();
+            let candidates: Vec<String> = entry.candidates.iter().map(|c| json_string(c)).collect();
+            steps.push(format!(
+                "    {{\"technique\": {}, \"description\": {}, \"placements\": {}, \"eliminations\": {}, \"grid\": {}, \"candidates\": [{}]}}",
+                json_string(step.get_technique().get_name()),
+                json_string(step.get_description()),
+                json_cells(step.get_placements(), &self.cell_names),
+                json_cells(step.get_eliminations(), &self.cell_names),
+                json_string(&entry.grid),
+                candidates.join(", ")
+            ));
+        }
+        format!(
+            "{{\n  \"puzzle\": {},\n  \"solved\": {},\n  \"end\": {},\n  \"steps\": [\n{}\n  ]\n}}\n",
+            json_string(&self.puzzle),
+            self.solved,
+            json_string(&self.end),
+            steps.join(",\n")
+        )
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![format!("Puzzle: {}", self.puzzle)];
+        for (position, entry) in self.entries.iter().enumerate() {
+            lines.push(format!(
+                "{}. {} => {}",
+                position + 1,
+                entry.get_step(),
+                entry.get_result()
+            ));
+        }
+        if self.solved {
+            lines.push(format!("Solved: {}", self.end));
+        } else {
+            lines.push(format!(
+                "Stuck after {} steps: {}",
+                self.entries.len(),
+                self.end
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_of_a_puzzle() {
+        let sudoku = Sudoku::from_line(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let trace = solve_with_trace(&sudoku, &SolverOptions::new());
+        assert!(trace.is_solved());
+        assert_eq!(trace.get_entries().len(), 51);
+        let first = &trace.get_entries()[0];
+        assert_eq!(first.get_grid(), trace.get_puzzle());
+        assert_eq!(first.get_candidates().len(), 81);
+        assert_eq!(first.get_candidates()[0], "");
+        let text = trace.to_text();
+        assert!(text.starts_with("Puzzle: 53..7"));
+        assert!(text.ends_with(
+            "Solved: 534678912672195348198342567859761423426853791713924856961537284287419635345286179\n"
+        ));
+        let json = trace.to_json();
+        assert!(json.contains("\"solved\": true"));
+        assert_eq!(json.matches("\"technique\"").count(), 51);
+    }
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a \"b\"\\"), "\"a \\\"b\\\"\\\\\"");
+    }
+}
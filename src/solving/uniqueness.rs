@@ -0,0 +1,574 @@
+// deductions that rely on the puzzle having exactly one solution
+// a unique rectangle is a set of four empty cells in two rows, two columns and two sections that
+// share two candidates; if all four could only hold those two digits the puzzle would have two
+// solutions, so at least one of the cells has to be something else
+use super::{candidates, cells_with_candidate, combinations, common_peers, format_cells};
+use super::{format_digits, Step, Technique};
+use crate::datastructures::digit::{Digit, DigitHashSet};
+use crate::datastructures::sudoku::Sudoku;
+use std::collections::HashSet;
+
+struct Rectangle {
+    // ordered as top left, top right, bottom left, bottom right
+    corners: [usize; 4],
+    digits: [Digit; 2],
+}
+
+impl Rectangle {
+    fn pair(&self) -> DigitHashSet {
+        self.digits.iter().copied().collect()
+    }
+    fn is_floor(&self, sudoku: &Sudoku, position: usize) -> bool {
+        candidates(sudoku, self.corners[position]) == &self.pair()
+    }
+    fn floors(&self, sudoku: &Sudoku) -> Vec<usize> {
+        (0..4).filter(|p| self.is_floor(sudoku, *p)).collect()
+    }
+    fn roofs(&self, sudoku: &Sudoku) -> Vec<usize> {
+        (0..4).filter(|p| !self.is_floor(sudoku, *p)).collect()
+    }
+    fn extras(&self, sudoku: &Sudoku, position: usize) -> DigitHashSet {
+        candidates(sudoku, self.corners[position])
+            .difference(&self.pair())
+            .copied()
+            .collect()
+    }
+    fn cells(&self, positions: &[usize]) -> Vec<usize> {
+        positions.iter().map(|p| self.corners[*p]).collect()
+    }
+    fn description(&self, sudoku: &Sudoku) -> String {
+        format!(
+            "{}/{} in {}",
+            self.digits[0].to_usize(),
+            self.digits[1].to_usize(),
+            format_cells(sudoku, &self.corners)
+        )
+    }
+}
+
+fn are_opposite(first: usize, second: usize) -> bool {
+    first + second == 3
+}
+
+fn find_rectangles(sudoku: &Sudoku) -> Vec<Rectangle> {
+    let dimensions = sudoku.get_grid_dimensions();
+    let rows = *dimensions.get_row_count();
+    let columns = *dimensions.get_column_count();
+    let mut result = Vec::new();
+    for top in 0..rows {
+        for bottom in top + 1..rows {
+            for left in 0..columns {
+                for right in left + 1..columns {
+                    let corners = [
+                        top * columns + left,
+                        top * columns + right,
+                        bottom * columns + left,
+                        bottom * columns + right,
+                    ];
+                    let cells: Vec<_> = corners
+                        .iter()
+                        .map(|i| sudoku.get_cell(*i).unwrap())
+                        .collect();
+                    if cells.iter().any(|c| !c.is_empty()) {
+                        continue;
+                    }
+                    let sections: HashSet<usize> = cells.iter().map(|c| *c.get_section()).collect();
+                    if sections.len() != 2 {
+                        continue;
+                    }
+                    let mut common: Vec<Digit> = cells[0]
+                        .get_candidates()
+                        .iter()
+                        .copied()
+                        .filter(|d| cells.iter().all(|c| c.has_candidate(*d)))
+                        .collect();
+                    common.sort_by_key(|d| d.to_usize());
+                    for pair in combinations(&common, 2) {
+                        result.push(Rectangle {
+                            corners,
+                            digits: [pair[0], pair[1]],
+                        });
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+fn houses_containing(sudoku: &Sudoku, cells: &[usize]) -> Vec<Vec<usize>> {
+    sudoku
+        .get_houses()
+        .iter()
+        .filter(|house| cells.iter().all(|c| house.contains(*c)))
+        .map(|house| house.get_cells().clone())
+        .collect()
+}
+
+fn eliminations_for(
+    sudoku: &Sudoku,
+    cells: &[usize],
+    digits: &DigitHashSet,
+) -> Vec<(usize, Digit)> {
+    let mut sorted: Vec<Digit> = digits.iter().copied().collect();
+    sorted.sort_by_key(|d| d.to_usize());
+    let mut result = Vec::new();
+    for cell in cells {
+        for digit in &sorted {
+            if candidates(sudoku, *cell).contains(digit) {
+                result.push((*cell, *digit));
+            }
+        }
+    }
+    result
+}
+
+fn find_in_rectangles<F>(sudoku: &Sudoku, check: F) -> Option<Step>
+where
+    F: Fn(&Sudoku, &Rectangle) -> Option<Step>,
+{
+    find_rectangles(sudoku)
+        .iter()
+        .find_map(|rectangle| check(sudoku, rectangle))
+}
+
+// three corners hold only the pair, so the fourth can not be either digit
+pub fn find_unique_rectangle_type_1(sudoku: &Sudoku) -> Option<Step> {
+    find_in_rectangles(sudoku, |sudoku, rectangle| {
+        let roofs = rectangle.roofs(sudoku);
+        if roofs.len() != 1 {
+            return None;
+        }
+        let eliminations = eliminations_for(sudoku, &rectangle.cells(&roofs), &rectangle.pair());
+        Some(Step::new(
+            Technique::UniqueRectangleType1,
+            Vec::new(),
+            eliminations,
+            rectangle.description(sudoku),
+        ))
+    })
+}
+
+// the non bivalue corners share a single extra candidate, one of them has to be that digit
+fn find_single_extra(sudoku: &Sudoku, rectangle: &Rectangle, type_5: bool) -> Option<Step> {
+    let roofs = rectangle.roofs(sudoku);
+    if roofs.len() < 2 || roofs.len() > 3 {
+        return None;
+    }
+    let in_line = roofs.len() == 2 && !are_opposite(roofs[0], roofs[1]);
+    if in_line == type_5 {
+        return None;
+    }
+    let extra = rectangle.extras(sudoku, roofs[0]);
+    if extra.len() != 1 || roofs.iter().any(|r| rectangle.extras(sudoku, *r) != extra) {
+        return None;
+    }
+    let roof_cells = rectangle.cells(&roofs);
+    let eliminations = eliminations_for(sudoku, &common_peers(sudoku, &roof_cells), &extra);
+    if eliminations.is_empty() {
+        return None;
+    }
+    let technique = if type_5 {
+        Technique::UniqueRectangleType5
+    } else {
+        Technique::UniqueRectangleType2
+    };
+    Some(Step::new(
+        technique,
+        Vec::new(),
+        eliminations,
+        format!(
+            "{}, extra candidate {} in {}",
+            rectangle.description(sudoku),
+            format_digits(&extra),
+            format_cells(sudoku, &roof_cells)
+        ),
+    ))
+}
+
+pub fn find_unique_rectangle_type_2(sudoku: &Sudoku) -> Option<Step> {
+    find_in_rectangles(sudoku, |sudoku, rectangle| {
+        find_single_extra(sudoku, rectangle, false)
+    })
+}
+
+// the two roof cells and their extra candidates act as one cell of a naked subset
+pub fn find_unique_rectangle_type_3(sudoku: &Sudoku) -> Option<Step> {
+    find_in_rectangles(sudoku, |sudoku, rectangle| {
+        let roofs = rectangle.roofs(sudoku);
+        if roofs.len() != 2 || are_opposite(roofs[0], roofs[1]) {
+            return None;
+        }
+        let extras: DigitHashSet = rectangle
+            .extras(sudoku, roofs[0])
+            .union(&rectangle.extras(sudoku, roofs[1]))
+            .copied()
+            .collect();
+        let roof_cells = rectangle.cells(&roofs);
+        for house in houses_containing(sudoku, &roof_cells) {
+            let others: Vec<usize> = house
+                .iter()
+                .copied()
+                .filter(|i| !roof_cells.contains(i) && sudoku.get_cell(*i).unwrap().is_empty())
+                .collect();
+            for size in 1..=3 {
+                for subset in combinations(&others, size) {
+                    let mut digits = extras.clone();
+                    for cell in &subset {
+                        digits.extend(candidates(sudoku, *cell).iter().copied());
+                    }
+                    if digits.len() != size + 1 {
+                        continue;
+                    }
+                    let targets: Vec<usize> = others
+                        .iter()
+                        .copied()
+                        .filter(|i| !subset.contains(i))
+                        .collect();
+                    let eliminations = eliminations_for(sudoku, &targets, &digits);
+                    if !eliminations.is_empty() {
+                        return Some(Step::new(
+                            Technique::UniqueRectangleType3,
+                            Vec::new(),
+                            eliminations,
+                            format!(
+                                "{}, naked subset {} in {} with {}",
+                                rectangle.description(sudoku),
+                                format_digits(&digits),
+                                format_cells(sudoku, &subset),
+                                format_cells(sudoku, &roof_cells)
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+        None
+    })
+}
+
+// one digit of the pair is locked into the roof cells, so the other one can not be there
+pub fn find_unique_rectangle_type_4(sudoku: &Sudoku) -> Option<Step> {
+    find_in_rectangles(sudoku, |sudoku, rectangle| {
+        let roofs = rectangle.roofs(sudoku);
+        if roofs.len() != 2 || are_opposite(roofs[0], roofs[1]) {
+            return None;
+        }
+        let roof_cells = rectangle.cells(&roofs);
+        for house in houses_containing(sudoku, &roof_cells) {
+            for (locked, other) in [(0, 1), (1, 0)] {
+                let positions = cells_with_candidate(sudoku, &house, rectangle.digits[locked]);
+                if positions.iter().all(|i| roof_cells.contains(i)) {
+                    let removed: DigitHashSet = [rectangle.digits[other]].iter().copied().collect();
+                    return Some(Step::new(
+                        Technique::UniqueRectangleType4,
+                        Vec::new(),
+                        eliminations_for(sudoku, &roof_cells, &removed),
+                        format!(
+                            "{}, {} locked in {}",
+                            rectangle.description(sudoku),
+                            rectangle.digits[locked].to_usize(),
+                            format_cells(sudoku, &roof_cells)
+                        ),
+                    ));
+                }
+            }
+        }
+        None
+    })
+}
+
+pub fn find_unique_rectangle_type_5(sudoku: &Sudoku) -> Option<Step> {
+    find_in_rectangles(sudoku, |sudoku, rectangle| {
+        find_single_extra(sudoku, rectangle, true)
+    })
+}
+
+// with the roofs on a diagonal, a digit of the pair forming an x-wing on the rectangle can not be
+// in the roofs, otherwise both floors would hold the other digit
+pub fn find_unique_rectangle_type_6(sudoku: &Sudoku) -> Option<Step> {
+    find_in_rectangles(sudoku, |sudoku, rectangle| {
+        let roofs = rectangle.roofs(sudoku);
+        if roofs.len() != 2 || !are_opposite(roofs[0], roofs[1]) {
+            return None;
+        }
+        let dimensions = sudoku.get_grid_dimensions();
+        let corners = rectangle.corners;
+        let rows = [
+            dimensions.get_indices_for_row(dimensions.get_row_for_index(corners[0])),
+            dimensions.get_indices_for_row(dimensions.get_row_for_index(corners[3])),
+        ];
+        let columns = [
+            dimensions.get_indices_for_column(dimensions.get_column_for_index(corners[0])),
+            dimensions.get_indices_for_column(dimensions.get_column_for_index(corners[3])),
+        ];
+        for digit in rectangle.digits.iter() {
+            let confined = |lines: &[Vec<usize>; 2]| {
+                lines.iter().all(|line| {
+                    cells_with_candidate(sudoku, line, *digit)
+                        .iter()
+                        .all(|i| corners.contains(i))
+                })
+            };
+            if confined(&rows) || confined(&columns) {
+                let removed: DigitHashSet = [*digit].iter().copied().collect();
+                return Some(Step::new(
+                    Technique::UniqueRectangleType6,
+                    Vec::new(),
+                    eliminations_for(sudoku, &rectangle.cells(&roofs), &removed),
+                    format!(
+                        "{}, x-wing on {}",
+                        rectangle.description(sudoku),
+                        digit.to_usize()
+                    ),
+                ));
+            }
+        }
+        None
+    })
+}
+
+// a bivalue corner and strong links on one digit from the opposite corner along its row and column
+pub fn find_hidden_unique_rectangle(sudoku: &Sudoku) -> Option<Step> {
+    find_in_rectangles(sudoku, |sudoku, rectangle| {
+        let dimensions = sudoku.get_grid_dimensions();
+        for floor in rectangle.floors(sudoku) {
+            let opposite = 3 - floor;
+            let target = rectangle.corners[opposite];
+            let row = dimensions.get_indices_for_row(dimensions.get_row_for_index(target));
+            let column = dimensions.get_indices_for_column(dimensions.get_column_for_index(target));
+            for (linked, other) in [(0, 1), (1, 0)] {
+                let digit = rectangle.digits[linked];
+                let in_rectangle = |line: &Vec<usize>| {
+                    cells_with_candidate(sudoku, line, digit)
+                        .iter()
+                        .all(|i| rectangle.corners.contains(i))
+                };
+                if in_rectangle(&row) && in_rectangle(&column) {
+                    return Some(Step::new(
+                        Technique::HiddenUniqueRectangle,
+                        Vec::new(),
+                        vec![(target, rectangle.digits[other])],
+                        format!(
+                            "{}, strong links on {} from {}",
+                            rectangle.description(sudoku),
+                            digit.to_usize(),
+                            format_cells(sudoku, &[target])
+                        ),
+                    ));
+                }
+            }
+        }
+        None
+    })
+}
+
+// bivalue universal grave plus one: every empty cell is bivalue except one with three candidates,
+// and every digit appears twice in each house except for the digit that must go in that cell
+pub fn find_bug_plus_one(sudoku: &Sudoku) -> Option<Step> {
+    let empty: Vec<usize> = sudoku
+        .cells()
+        .filter(|c| c.is_empty())
+        .map(|c| *c.get_index())
+        .collect();
+    let mut trivalue: Option<usize> = None;
+    for index in &empty {
+        match candidates(sudoku, *index).len() {
+            2 => {}
+            3 if trivalue.is_none() => trivalue = Some(*index),
+            _ => return None,
+        }
+    }
+    let cell = trivalue?;
+    let mut found: Option<Digit> = None;
+    for house in sudoku.get_houses() {
+        let contains_cell = house.contains(cell);
+        let mut tripled: Vec<Digit> = Vec::new();
+        for digit in sudoku.get_digits() {
+            match cells_with_candidate(sudoku, house.get_cells(), digit).len() {
+                0 | 2 => {}
+                3 if contains_cell => tripled.push(digit),
+                _ => return None,
+            }
+        }
+        if contains_cell {
+            if tripled.len() != 1 || found.is_some_and(|d| d != tripled[0]) {
+                return None;
+            }
+            found = Some(tripled[0]);
+        }
+    }
+    let digit = found?;
+    Some(Step::new(
+        Technique::BugPlusOne,
+        vec![(cell, digit)],
+        Vec::new(),
+        format!(
+            "{} is the only cell with three candidates",
+            format_cells(sudoku, &[cell])
+        ),
+    ))
+}
+
+pub fn find_step(sudoku: &Sudoku) -> Option<Step> {
+    let finders: [fn(&Sudoku) -> Option<Step>; 8] = [
+        find_unique_rectangle_type_1,
+        find_unique_rectangle_type_2,
+        find_unique_rectangle_type_3,
+        find_unique_rectangle_type_4,
+        find_unique_rectangle_type_5,
+        find_unique_rectangle_type_6,
+        find_hidden_unique_rectangle,
+        find_bug_plus_one,
+    ];
+    finders.iter().find_map(|finder| finder(sudoku))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::DigitSet;
+    use Digit::*;
+
+    fn set_candidates(sudoku: &mut Sudoku, index: usize, digits: &[Digit]) {
+        let digits: DigitHashSet = digits.iter().copied().collect();
+        sudoku.get_mut_cell(index).unwrap().set_candidates(digits);
+    }
+
+    #[test]
+    fn test_unique_rectangle_type_1() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        for index in [0, 3, 9] {
+            set_candidates(&mut sudoku, index, &[One, Two]);
+        }
+        set_candidates(&mut sudoku, 12, &[One, Two, Five]);
+        let step = find_unique_rectangle_type_1(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(12, One), (12, Two)]);
+    }
+    #[test]
+    fn test_unique_rectangle_type_2() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        set_candidates(&mut sudoku, 0, &[One, Two]);
+        set_candidates(&mut sudoku, 3, &[One, Two]);
+        set_candidates(&mut sudoku, 9, &[One, Two, Seven]);
+        set_candidates(&mut sudoku, 12, &[One, Two, Seven]);
+        assert_eq!(find_unique_rectangle_type_1(&sudoku), None);
+        let step = find_unique_rectangle_type_2(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations().len(), 7);
+        assert!(step
+            .get_eliminations()
+            .iter()
+            .all(|(i, d)| *d == Seven && *i / 9 == 1));
+    }
+    #[test]
+    fn test_unique_rectangle_type_3() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        set_candidates(&mut sudoku, 0, &[One, Two]);
+        set_candidates(&mut sudoku, 3, &[One, Two]);
+        set_candidates(&mut sudoku, 9, &[One, Two, Seven]);
+        set_candidates(&mut sudoku, 12, &[One, Two, Eight]);
+        // with r2c7 the roofs make a naked pair on seven and eight in row 2
+        set_candidates(&mut sudoku, 15, &[Seven, Eight]);
+        let step = find_unique_rectangle_type_3(&sudoku).unwrap();
+        let expected: Vec<(usize, Digit)> = [10, 11, 13, 14, 16, 17]
+            .iter()
+            .flat_map(|i| [(*i, Seven), (*i, Eight)])
+            .collect();
+        assert_eq!(step.get_eliminations(), &expected);
+    }
+    #[test]
+    fn test_unique_rectangle_type_4() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        set_candidates(&mut sudoku, 0, &[One, Two]);
+        set_candidates(&mut sudoku, 3, &[One, Two]);
+        set_candidates(&mut sudoku, 9, &[One, Two, Five, Six]);
+        set_candidates(&mut sudoku, 12, &[One, Two, Six, Seven]);
+        for index in 9..18 {
+            if index != 9 && index != 12 {
+                sudoku.remove_candidate(index, One);
+            }
+        }
+        let step = find_unique_rectangle_type_4(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(9, Two), (12, Two)]);
+    }
+    #[test]
+    fn test_unique_rectangle_type_5() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        set_candidates(&mut sudoku, 0, &[One, Two]);
+        set_candidates(&mut sudoku, 12, &[One, Two]);
+        set_candidates(&mut sudoku, 3, &[One, Two, Seven]);
+        set_candidates(&mut sudoku, 9, &[One, Two, Seven]);
+        assert_eq!(find_unique_rectangle_type_2(&sudoku), None);
+        let step = find_unique_rectangle_type_5(&sudoku).unwrap();
+        assert_eq!(
+            step.get_eliminations(),
+            &vec![(1, Seven), (2, Seven), (13, Seven), (14, Seven)]
+        );
+    }
+    #[test]
+    fn test_unique_rectangle_type_6() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        set_candidates(&mut sudoku, 0, &[One, Two]);
+        set_candidates(&mut sudoku, 12, &[One, Two]);
+        set_candidates(&mut sudoku, 3, &[One, Two, Five]);
+        set_candidates(&mut sudoku, 9, &[One, Two, Six]);
+        // one is only left in the corners of rows 1 and 2
+        for index in (0..18).filter(|i| ![0, 3, 9, 12].contains(i)) {
+            sudoku.remove_candidate(index, One);
+        }
+        let step = find_unique_rectangle_type_6(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(3, One), (9, One)]);
+    }
+    #[test]
+    fn test_hidden_unique_rectangle() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        set_candidates(&mut sudoku, 0, &[One, Two]);
+        for index in sudoku.get_grid_dimensions().get_indices_for_row(1) {
+            if index != 9 && index != 12 {
+                sudoku.remove_candidate(index, One);
+            }
+        }
+        for index in sudoku.get_grid_dimensions().get_indices_for_column(3) {
+            if index != 3 && index != 12 {
+                sudoku.remove_candidate(index, One);
+            }
+        }
+        let step = find_hidden_unique_rectangle(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(12, Two)]);
+    }
+    #[test]
+    fn test_bug_plus_one() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.distribute_set_to_make_correct_sudoku(DigitSet::new_full());
+        let layout: [(usize, &[Digit]); 12] = [
+            (0, &[One, Two, Three]),
+            (3, &[One, Two]),
+            (9, &[One, Two]),
+            (12, &[One, Two]),
+            (1, &[Three, Four]),
+            (2, &[Three, Four]),
+            (55, &[Three, Four]),
+            (56, &[Three, Four]),
+            (27, &[Three, Five]),
+            (36, &[Three, Five]),
+            (35, &[Three, Five]),
+            (44, &[Three, Five]),
+        ];
+        for (index, digits) in layout.iter() {
+            sudoku.get_mut_cell(*index).unwrap().set_value(None);
+            set_candidates(&mut sudoku, *index, digits);
+        }
+        let step = find_bug_plus_one(&sudoku).unwrap();
+        assert_eq!(step.get_placements(), &vec![(0, Three)]);
+        set_candidates(&mut sudoku, 44, &[Three, Five, Six]);
+        assert_eq!(find_bug_plus_one(&sudoku), None);
+    }
+    #[test]
+    fn test_no_rectangle_inside_one_section() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        for index in [0, 1, 9] {
+            set_candidates(&mut sudoku, index, &[One, Two]);
+        }
+        assert_eq!(find_unique_rectangle_type_1(&sudoku), None);
+    }
+}
@@ -0,0 +1,50 @@
+// the rules of a variant other than houses, cages and rules the peers already cover, each
+// removing the candidates it allows no more given the others
+use super::{format_cells, rule_eliminations, Step, Technique};
+use crate::datastructures::cage::Cage;
+use crate::datastructures::sudoku::Sudoku;
+
+pub fn find_variant_rule(sudoku: &Sudoku) -> Option<Step> {
+    for rule in sudoku.get_constraints() {
+        if rule.is::<Cage>() || rule.is_pairwise() {
+            continue;
+        }
+        let eliminations = rule_eliminations(sudoku, rule.as_ref());
+        if !eliminations.is_empty() {
+            return Some(Step::new(
+                Technique::VariantRule,
+                Vec::new(),
+                eliminations,
+                format!(
+                    "{} {}",
+                    rule.get_name(),
+                    format_cells(sudoku, &rule.get_cells())
+                ),
+            ));
+        }
+    }
+    None
+}
+
+pub fn find_step(sudoku: &Sudoku) -> Option<Step> {
+    find_variant_rule(sudoku)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastructures::digit::Digit;
+    use crate::datastructures::lines::Thermometer;
+
+    #[test]
+    fn test_thermometer_after_an_elimination() {
+        let mut sudoku = Sudoku::new(9, 9, 3, 3);
+        sudoku.add_constraint(Box::new(Thermometer::new(vec![0, 1])));
+        assert!(find_variant_rule(&sudoku).is_none());
+        // without a nine at the tip the bulb can not be an eight
+        sudoku.remove_candidate(1, Digit::Nine);
+        let step = find_variant_rule(&sudoku).unwrap();
+        assert_eq!(step.get_eliminations(), &vec![(0, Digit::Eight)]);
+        assert_eq!(step.get_description(), "thermometer r1c1,r1c2");
+    }
+}
@@ -0,0 +1,243 @@
+// the automorphisms of a filled grid: the symmetries that, together with a relabelling of the
+// digits, turn the grid into itself
+// for every order of the columns the rows are picked one at a time; the first row fixes the
+// relabelling and every later row has to match the grid under it, which leaves few orders to try
+use super::{line_orders, next_lines, transpose, values};
+use crate::datastructures::digit::Digit;
+use crate::datastructures::sudoku::Sudoku;
+use std::collections::{HashSet, VecDeque};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Automorphism {
+    // the grid is transposed first, then row r of the result is source row rows[r], column c is
+    // source column columns[c] and digit d is written as digits[d - 1]
+    transposed: bool,
+    rows: Vec<usize>,
+    columns: Vec<usize>,
+    digits: Vec<Digit>,
+}
+
+impl Automorphism {
+    pub fn is_transposed(&self) -> &bool {
+        &self.transposed
+    }
+    pub fn get_rows(&self) -> &Vec<usize> {
+        &self.rows
+    }
+    pub fn get_columns(&self) -> &Vec<usize> {
+        &self.columns
+    }
+    pub fn get_digits(&self) -> &Vec<Digit> {
+        &self.digits
+    }
+    // the symmetry that changes nothing
+    pub fn is_identity(&self) -> bool {
+        let size = self.rows.len();
+        !self.transposed
+            && self.rows == (0..size).collect::<Vec<usize>>()
+            && self.columns == (0..size).collect::<Vec<usize>>()
+    }
+    // for every cell the cell its value comes from
+    pub fn get_cell_map(&self) -> Vec<usize> {
+        let size = self.rows.len();
+        (0..size * size)
+            .map(|index| {
+                let (row, column) = (self.rows[index / size], self.columns[index % size]);
+                if self.transposed {
+                    column * size + row
+                } else {
+                    row * size + column
+                }
+            })
+            .collect()
+    }
+    // the values of grid moved and relabelled, a grid without values for the cells it had none
+    pub fn apply(&self, sudoku: &Sudoku) -> Sudoku {
+        let mut result = sudoku.clone();
+        for (index, source) in self.get_cell_map().into_iter().enumerate() {
+            let value = sudoku
+                .get_cell(source)
+                .unwrap()
+                .get_value()
+                .map(|d| self.digits[d.to_usize() - 1]);
+            result.get_mut_cell(index).unwrap().set_value(value);
+        }
+        result.reset_candidates();
+        result
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AutomorphismGroup {
+    // the identity comes first
+    elements: Vec<Automorphism>,
+    generators: Vec<Automorphism>,
+}
+
+impl AutomorphismGroup {
+    pub fn get_size(&self) -> usize {
+        self.elements.len()
+    }
+    pub fn get_elements(&self) -> &Vec<Automorphism> {
+        &self.elements
+    }
+    // a few elements every element is a product of, none of them the identity
+    pub fn get_generators(&self) -> &Vec<Automorphism> {
+        &self.generators
+    }
+}
+
+struct Search<'a> {
+    grid: &'a [usize],
+    source: &'a [usize],
+    size: usize,
+    band_height: usize,
+    columns: &'a [usize],
+    rows: Vec<usize>,
+    found: Vec<(Vec<usize>, Vec<usize>)>,
+}
+
+impl<'a> Search<'a> {
+    // labels[d] is what source digit d becomes, 0 while it is not known
+    fn run(&mut self, labels: &[usize]) {
+        let slot = self.rows.len();
+        if slot == self.size {
+            self.found.push((self.rows.clone(), labels.to_vec()));
+            return;
+        }
+        for row in next_lines(&self.rows, self.size, self.band_height) {
+            let mut labels = labels.to_vec();
+            let fits = self.columns.iter().enumerate().all(|(position, column)| {
+                let digit = self.source[row * self.size + column];
+                let target = self.grid[slot * self.size + position];
+                if labels[digit] == 0 {
+                    labels[digit] = target;
+                }
+                labels[digit] == target
+            });
+            if fits {
+                self.rows.push(row);
+                self.run(&labels);
+                self.rows.pop();
+            }
+        }
+    }
+}
+
+// the cell maps of every product of generators, starting from the identity
+fn closure(generators: &[Vec<usize>], cell_count: usize) -> HashSet<Vec<usize>> {
+    let identity: Vec<usize> = (0..cell_count).collect();
+    let mut seen: HashSet<Vec<usize>> = HashSet::new();
+    let mut queue: VecDeque<Vec<usize>> = VecDeque::new();
+    seen.insert(identity.clone());
+    queue.push_back(identity);
+    while let Some(map) = queue.pop_front() {
+        for generator in generators {
+            // first map, then generator
+            let product: Vec<usize> = generator.iter().map(|cell| map[*cell]).collect();
+            if seen.insert(product.clone()) {
+                queue.push_back(product);
+            }
+        }
+    }
+    seen
+}
+
+// the automorphism group of a filled grid, None for grids that are not filled or not standard
+pub fn automorphism_group(sudoku: &Sudoku) -> Option<AutomorphismGroup> {
+    if !sudoku.has_regular_sections() || !sudoku.is_solved() {
+        return None;
+    }
+    let dimensions = sudoku.get_grid_dimensions();
+    let size = *dimensions.get_column_count();
+    let (width, height) = (
+        *dimensions.get_section_width(),
+        *dimensions.get_section_height(),
+    );
+    let grid = values(sudoku);
+    let mut sources = vec![(false, grid.clone())];
+    if width == height {
+        sources.push((true, transpose(&grid, size)));
+    }
+    let mut elements = Vec::new();
+    for (transposed, source) in &sources {
+        for columns in line_orders(size, width) {
+            let mut search = Search {
+                grid: &grid,
+                source,
+                size,
+                band_height: height,
+                columns: &columns,
+                rows: Vec::new(),
+                found: Vec::new(),
+            };
+            search.run(&vec![0; size + 1]);
+            for (rows, labels) in search.found {
+                elements.push(Automorphism {
+                    transposed: *transposed,
+                    rows,
+                    columns: columns.clone(),
+                    digits: labels[1..]
+                        .iter()
+                        .map(|label| Digit::from_usize(*label).unwrap())
+                        .collect(),
+                });
+            }
+        }
+    }
+    elements.sort_by_key(|element| !element.is_identity());
+    // an element joins the generators when the ones so far do not give it
+    let mut generators: Vec<Automorphism> = Vec::new();
+    let mut maps: Vec<Vec<usize>> = Vec::new();
+    let mut generated = closure(&maps, grid.len());
+    for element in &elements {
+        let map = element.get_cell_map();
+        if !generated.contains(&map) {
+            maps.push(map);
+            generators.push(element.clone());
+            generated = closure(&maps, grid.len());
+        }
+    }
+    Some(AutomorphismGroup {
+        elements,
+        generators,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_automorphisms_of_a_symmetric_grid() {
+        let sudoku = Sudoku::from_line(
+            "123456789456789123789123456231564897564897231897231564312645978645978312978312645",
+        )
+        .unwrap();
+        let group = automorphism_group(&sudoku).unwrap();
+        assert!(group.get_elements()[0].is_identity());
+        assert_eq!(group.get_size(), 648);
+        for element in group.get_elements() {
+            assert_eq!(element.apply(&sudoku).to_line(), sudoku.to_line());
+        }
+        let maps: Vec<Vec<usize>> = group
+            .get_generators()
+            .iter()
+            .map(|generator| generator.get_cell_map())
+            .collect();
+        assert!(maps.len() < 10);
+        assert_eq!(closure(&maps, 81).len(), 648);
+    }
+    #[test]
+    fn test_automorphisms_of_a_plain_grid() {
+        let sudoku = Sudoku::from_line(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+        )
+        .unwrap();
+        let group = automorphism_group(&sudoku).unwrap();
+        assert_eq!(group.get_size(), 1);
+        assert!(group.get_generators().is_empty());
+        let puzzle = Sudoku::from_line(&sudoku.to_line().replacen('5', ".", 1)).unwrap();
+        assert!(automorphism_group(&puzzle).is_none());
+    }
+}
@@ -0,0 +1,157 @@
+// the canonical form of a grid: of all the grids the symmetries turn it into, the one that reads
+// smallest row by row with empty cells as 0 (the "minlex" form), so two grids are copies of each
+// other exactly when their canonical forms are the same
+// for an order of the columns the rows are picked one at a time and an order is dropped as soon
+// as its rows so far read larger than the best grid found; digits are relabelled in the order
+// they are met, which makes every grid read as small as it can
+use super::{line_orders, next_lines, transpose, values};
+use crate::datastructures::digit::Digit;
+use crate::datastructures::sudoku::Sudoku;
+use std::cmp::Ordering;
+
+struct Search<'a> {
+    grid: &'a [usize],
+    size: usize,
+    band_height: usize,
+    // the source column of every column
+    columns: &'a [usize],
+    // the source row of every row placed so far and the values of those rows
+    rows: Vec<usize>,
+    current: Vec<usize>,
+    best: Option<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    fn run(&mut self, labels: &[usize], next_label: usize) {
+        let slot = self.rows.len();
+        if slot == self.size {
+            self.best = Some(self.current.clone());
+            return;
+        }
+        let start = slot * self.size;
+        for row in next_lines(&self.rows, self.size, self.band_height) {
+            let mut labels = labels.to_vec();
+            let mut next_label = next_label;
+            let values: Vec<usize> = self
+                .columns
+                .iter()
+                .map(|column| {
+                    let value = self.grid[row * self.size + column];
+                    if value != 0 && labels[value] == 0 {
+                        labels[value] = next_label;
+                        next_label += 1;
+                    }
+                    labels[value]
+                })
+                .collect();
+            // only a grid that has matched the best so far can be beaten by its next row alone
+            if let Some(best) = &self.best {
+                if self.current[..start] == best[..start] {
+                    match values.as_slice().cmp(&best[start..start + self.size]) {
+                        Ordering::Greater => continue,
+                        Ordering::Equal if slot + 1 == self.size => continue,
+                        _ => (),
+                    }
+                }
+            }
+            self.current.truncate(start);
+            self.current.extend(values);
+            self.rows.push(row);
+            self.run(&labels, next_label);
+            self.rows.pop();
+        }
+    }
+}
+
+// the canonical form as a line of digits with '.' for empty cells, None for grids with other
+// rules than rows, columns and regular sections
+pub fn canonical_form(sudoku: &Sudoku) -> Option<String> {
+    if !sudoku.has_regular_sections() {
+        return None;
+    }
+    let dimensions = sudoku.get_grid_dimensions();
+    let size = *dimensions.get_column_count();
+    let (width, height) = (
+        *dimensions.get_section_width(),
+        *dimensions.get_section_height(),
+    );
+    let grid = values(sudoku);
+    let mut grids = vec![grid.clone()];
+    if width == height {
+        grids.push(transpose(&grid, size));
+    }
+    let mut best: Option<Vec<usize>> = None;
+    for grid in &grids {
+        for columns in line_orders(size, width) {
+            let mut search = Search {
+                grid,
+                size,
+                band_height: height,
+                columns: &columns,
+                rows: Vec::new(),
+                current: Vec::new(),
+                best: best.take(),
+            };
+            search.run(&vec![0; size + 1], 1);
+            best = search.best;
+        }
+    }
+    Some(
+        best?
+            .iter()
+            .map(|value| Digit::from_usize(*value).map_or('.', |d| d.to_char()))
+            .collect(),
+    )
+}
+
+// whether the symmetries turn one grid into the other
+pub fn are_equivalent(first: &Sudoku, second: &Sudoku) -> bool {
+    first.get_grid_dimensions() == second.get_grid_dimensions()
+        && canonical_form(first).is_some_and(|form| Some(form) == canonical_form(second))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    // the puzzle transposed with every digit d written as 10 - d
+    fn copy_of_puzzle() -> String {
+        let characters: Vec<char> = PUZZLE.chars().collect();
+        (0..81)
+            .map(|index| {
+                let character = characters[(index % 9) * 9 + index / 9];
+                match character.to_digit(10) {
+                    Some(value) => std::char::from_digit(10 - value, 10).unwrap(),
+                    None => '.',
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let form = canonical_form(&sudoku).unwrap();
+        assert_eq!(form.chars().filter(|c| *c != '.').count(), 30);
+        let again = Sudoku::from_line(&form).unwrap();
+        assert_eq!(canonical_form(&again).unwrap(), form);
+        let solution = sudoku.get_solution().unwrap();
+        assert!(canonical_form(&solution).unwrap().starts_with("123456789"));
+    }
+    #[test]
+    fn test_equivalence() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let copy = Sudoku::from_line(&copy_of_puzzle()).unwrap();
+        assert!(are_equivalent(&sudoku, &copy));
+        let mut other = sudoku.clone();
+        other.get_mut_cell(2).unwrap().set_value(Some(Digit::One));
+        assert!(!are_equivalent(&sudoku, &other));
+        let mut diagonal = sudoku.clone();
+        diagonal.add_diagonals();
+        assert!(canonical_form(&diagonal).is_none());
+        assert!(!are_equivalent(&diagonal, &diagonal));
+    }
+}
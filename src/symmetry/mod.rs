@@ -0,0 +1,102 @@
+// the ways a grid can be changed without changing the puzzle: relabelling digits, reordering
+// bands and stacks and the lines inside them, and transposing when the sections are square
+pub mod automorphism;
+pub mod canonical;
+
+pub use automorphism::{automorphism_group, Automorphism, AutomorphismGroup};
+pub use canonical::{are_equivalent, canonical_form};
+
+use crate::datastructures::sudoku::Sudoku;
+
+// the grid as numbers row by row, 0 for an empty cell
+pub fn values(sudoku: &Sudoku) -> Vec<usize> {
+    (0..sudoku.get_grid_dimensions().get_data_size())
+        .map(|index| {
+            sudoku
+                .get_cell(index)
+                .unwrap()
+                .get_value()
+                .map_or(0, |d| d.to_usize())
+        })
+        .collect()
+}
+
+// the values of a square grid of size lines with rows and columns swapped
+pub fn transpose(grid: &[usize], size: usize) -> Vec<usize> {
+    (0..size * size)
+        .map(|index| grid[(index % size) * size + index / size])
+        .collect()
+}
+
+// every order of 0..count, in lexicographic order
+pub fn permutations(count: usize) -> Vec<Vec<usize>> {
+    if count == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for first in 0..count {
+        for rest in permutations(count - 1) {
+            let mut order = vec![first];
+            order.extend(rest.iter().map(|i| if *i >= first { i + 1 } else { *i }));
+            result.push(order);
+        }
+    }
+    result
+}
+
+// every order of count lines that keeps the groups of group lines together: the groups are
+// reordered and the lines inside every group
+pub fn line_orders(count: usize, group: usize) -> Vec<Vec<usize>> {
+    let inside = permutations(group);
+    let mut result = Vec::new();
+    for groups in permutations(count / group) {
+        let mut orders: Vec<Vec<usize>> = vec![Vec::new()];
+        for first in groups.iter().map(|g| g * group) {
+            orders = orders
+                .iter()
+                .flat_map(|order| {
+                    inside.iter().map(move |lines| {
+                        let mut next = order.clone();
+                        next.extend(lines.iter().map(|line| first + line));
+                        next
+                    })
+                })
+                .collect();
+        }
+        result.extend(orders);
+    }
+    result
+}
+
+// the lines that can come after used when lines are picked one at a time keeping groups of
+// group lines together: any line of a group not used yet at the start of a group, the lines left
+// in the group of the line before otherwise
+pub fn next_lines(used: &[usize], count: usize, group: usize) -> Vec<usize> {
+    let slot = used.len();
+    (0..count)
+        .filter(|line| !used.contains(line))
+        .filter(|line| {
+            if slot.is_multiple_of(group) {
+                !used.iter().any(|other| other / group == line / group)
+            } else {
+                line / group == used[slot - 1] / group
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_orders() {
+        assert_eq!(permutations(3).len(), 6);
+        assert_eq!(permutations(3)[1], vec![0, 2, 1]);
+        let orders = line_orders(9, 3);
+        assert_eq!(orders.len(), 1296);
+        assert_eq!(orders[0], (0..9).collect::<Vec<usize>>());
+        assert!(orders.contains(&vec![7, 6, 8, 1, 0, 2, 3, 5, 4]));
+        assert_eq!(line_orders(6, 2).len(), 48);
+    }
+}